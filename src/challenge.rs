@@ -0,0 +1,92 @@
+// Challenges end after a fixed number of moves or a fixed time. Results are appended to a history file in
+// the home directory and ranked by score among the results of the same challenge.
+
+#[cfg(test)]
+mod test;
+
+use chrono::NaiveDate;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+
+use crate::daily;
+use crate::game::{Game, Rules};
+use crate::home_path;
+
+const HISTORY_FILE_NAME: &str = ".game2048_challenges";
+
+pub struct ChallengeResult {
+  challenge: String,
+  date:      NaiveDate,
+  score:     u32,
+  max_tile:  u16,
+  moves:     usize
+}
+
+// Identifies comparable games, e.g. "4x4,moves:100,evil". None, if the rules set no limit.
+pub fn name(size_x: usize, size_y: usize, rules: &Rules, evil: bool, preview: bool) -> Option<String> {
+  if rules.move_limit.is_none() && rules.time_limit.is_none() {
+    return None;
+  }
+  let mut parts = vec![format!("{}x{}", size_x, size_y)];
+  if let Some(moves) = rules.move_limit {
+    parts.push(format!("moves:{}", moves));
+  }
+  if let Some(time) = rules.time_limit {
+    parts.push(format!("time:{}s", time.as_secs_f64()));
+  }
+  if let Some(interval) = rules.spawn_interval {
+    parts.push(format!("spawn:{}s", interval.as_secs_f64()));
+  }
+  if evil {
+    parts.push(String::from("evil"));
+  }
+  if preview {
+    parts.push(String::from("preview"));
+  }
+  Some(parts.join(","))
+}
+
+impl ChallengeResult {
+  pub fn of(challenge: String, game: &Game) -> Self {
+    ChallengeResult { challenge,
+                      date: daily::today(),
+                      score: game.score(),
+                      max_tile: game.board.max_tile().map_or(0, |(_, value)| value),
+                      moves: game.shift_count() }
+  }
+
+  // Appends a line "<challenge> <date> <score> <max tile> <moves>" to the history file. Returns the rank of
+  // the score among all results of the challenge, and their number.
+  pub fn record(&self) -> io::Result<(usize, usize)> {
+    let path = home_path(HISTORY_FILE_NAME)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", self)?;
+    Ok(self.rank(&fs::read_to_string(&path)?))
+  }
+
+  // the rank of the score among the results of the challenge in `history`, and their number
+  fn rank(&self, history: &str) -> (usize, usize) {
+    let scores: Vec<u32> = history
+      .lines()
+      .filter_map(|line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+        [challenge, _, score, ..] if challenge == self.challenge => score.parse().ok(),
+        _ => None
+      })
+      .collect();
+    let rank = 1 + scores.iter().filter(|&&score| score > self.score).count();
+    (rank, scores.len())
+  }
+
+  pub fn summary(&self, (rank, results): (usize, usize)) -> String {
+    format!("2048 challenge {}: score {}, max tile {}, {} moves – rank {} of {}",
+            self.challenge, self.score, self.max_tile, self.moves, rank, results)
+  }
+}
+
+// a line of the history file
+impl fmt::Display for ChallengeResult {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {} {} {} {}", self.challenge, self.date, self.score, self.max_tile, self.moves)
+  }
+}
@@ -0,0 +1,26 @@
+use chrono::NaiveDate;
+use std::time::Duration;
+
+use super::{name, ChallengeResult};
+use crate::game::Rules;
+
+#[test]
+fn name_lists_the_rules() {
+  let rules = Rules { move_limit: Some(100), time_limit: Some(Duration::from_secs(90)), ..Rules::default() };
+  assert_eq!(name(4, 4, &rules, true, false), Some(String::from("4x4,moves:100,time:90s,evil")));
+  assert_eq!(name(4, 4, &Rules::classic(), false, false), None);
+}
+
+#[test]
+fn rank_among_results_of_the_same_challenge() {
+  let result = ChallengeResult { challenge: String::from("4x4,moves:100"),
+                                 date:      NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+                                 score:     500,
+                                 max_tile:  64,
+                                 moves:     100 };
+  assert_eq!(result.to_string(), "4x4,moves:100 2026-10-19 500 64 100");
+  let history = "4x4,moves:100 2026-10-01 800 128 100\n\
+                 4x4,moves:50 2026-10-02 900 128 50\n\
+                 4x4,moves:100 2026-10-03 300 32 100\n";
+  assert_eq!(result.rank(&format!("{}{}\n", history, result)), (2, 3));
+}
@@ -2,29 +2,84 @@ use std::cell::{Ref, RefMut};
 
 use crate::game::{Command, Game, GameState};
 use crate::views::View;
+use self::keys::Action;
 
+pub use self::api::ApiController;
+pub use self::editor::{BoardEditor, EditorExit};
+pub use self::engine::EngineController;
+pub use self::keys::{KeyMap, Settings};
 pub use self::ncurses::NCursesController;
 pub use self::console::ConsoleController;
+pub use self::race::RaceController;
+pub use self::script::ScriptController;
+pub use self::versus::VersusController;
 
+mod api;
 mod console;
+mod editor;
+mod engine;
+mod keys;
 mod ncurses;
+mod race;
+mod recorder;
+mod script;
+mod versus;
 
 pub trait Controller {
   fn receive_command(&self) -> Command;
 
   fn view(&self) -> &dyn View;
 
-  fn game(&self) -> Ref<Game>;
+  fn game(&self) -> Ref<'_, Game>;
 
-  fn mut_game(&self) -> RefMut<Game>;
+  fn mut_game(&self) -> RefMut<'_, Game>;
 
   fn run_game(&self) {
     loop {
       self.view().update();
-      self.mut_game().execute(self.receive_command());
+      let command = self.receive_command(); // may look at the game, e.g. for picking squares
+      self.mut_game().execute(command);
       if let GameState::Quit = self.game().state() {
         break;
       }
     }
   }
 }
+
+// the mode, the rules of `game` and the keys of `actions`, shown while the game is paused
+fn help_lines(game: &Game, mode: &str, key_map: &KeyMap, actions: &[Action]) -> Vec<String> {
+  let rules = game.rules();
+  let mut lines = vec![format!("Mode: {} on {}x{}", mode, game.board.size_x(), game.board.size_y())];
+  if let Some(goal) = game.goal() {
+    lines.push(format!("Goal: reach {}", goal));
+  }
+  if let Some(limit) = rules.move_limit {
+    lines.push(format!("Move limit: {}", limit));
+  }
+  if let Some(limit) = rules.time_limit {
+    lines.push(format!("Time limit: {:?}", limit));
+  }
+  if let Some(interval) = rules.spawn_interval {
+    lines.push(format!("A new tile every {:?}", interval));
+  }
+  if game.next_tile().is_some() {
+    lines.push(String::from("Preview of the next tile"));
+  }
+  lines.push(String::new());
+  lines.extend(key_map.help_lines(actions));
+  lines.push(String::new());
+  lines.push(String::from("Press any key to continue"));
+  lines
+}
+
+// the question to ask before `command` throws away a game in progress. None, if no confirmation is needed.
+fn confirmation_question(command: &Command, game: &Game, settings: Settings) -> Option<&'static str> {
+  if !settings.confirm || !game.in_progress() {
+    return None;
+  }
+  match command {
+    Command::New => Some("Start a new game?"),
+    Command::Quit => Some("Quit the game?"),
+    _ => None,
+  }
+}
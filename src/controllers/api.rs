@@ -0,0 +1,206 @@
+// A small HTTP API for playing over a local TCP port. Each request gets a JSON response:
+//
+//   GET  /state         {"score":4,"state":"running","board":[[2,0,0],[0,4,0],[0,0,0]],"moves":["left","up"]}
+//   GET  /moves         {"moves":["left","up"]}
+//   POST /move/left     (also right, up, down) the state after the move
+//   POST /new           the state of the new game
+//   POST /quit          ends the game
+//
+// The board is a list of rows, with 0 for empty squares. Errors are answered with {"error":"<reason>"}.
+
+#[cfg(test)]
+mod test;
+
+use log::{info, warn};
+use std::cell::{Ref, RefCell, RefMut};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use super::Controller;
+use crate::game::{Command, Direction, Game, GameState, Square};
+use crate::views::{NoView, View};
+
+// how long a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct ApiController<'a> {
+  game:     &'a RefCell<Game>,
+  view:     Box<dyn View + 'a>,
+  listener: TcpListener,
+  client:   RefCell<Option<TcpStream>> // waiting for the response to its command
+}
+
+impl<'a> ApiController<'a> {
+  // The game is shown in `view`, if there is one.
+  pub fn create(game: &'a RefCell<Game>, view: Option<Box<dyn View + 'a>>, listener: TcpListener)
+                -> ApiController<'a> {
+    ApiController { game,
+                    view: view.unwrap_or_else(|| Box::new(NoView)),
+                    listener,
+                    client: RefCell::new(None) }
+  }
+
+  // the next client with a request. Clients that do not send one in time are dropped.
+  fn next_request(&self) -> (TcpStream, String, String) {
+    loop {
+      let stream = match self.listener.accept() {
+        Ok((stream, _)) => stream,
+        Err(e) => {
+          warn!("Cannot accept a client: {}", e);
+          continue;
+        }
+      };
+      if let Err(e) = stream.set_read_timeout(Some(READ_TIMEOUT)) {
+        warn!("Cannot set a timeout for a client: {}", e);
+        continue;
+      }
+      match read_request(BufReader::new(&stream)) {
+        Ok((method, path)) => break (stream, method, path),
+        Err(e) => warn!("Cannot read a request: {}", e)
+      }
+    }
+  }
+}
+
+impl<'a> Controller for ApiController<'a> {
+  fn view(&self) -> &dyn View { self.view.as_ref() }
+
+  fn game(&self) -> Ref<'_, Game> { self.game.borrow() }
+
+  fn mut_game(&self) -> RefMut<'_, Game> { self.game.borrow_mut() }
+
+  // Answers queries until a request changes the game. Its client is answered after the change.
+  fn receive_command(&self) -> Command {
+    loop {
+      let (mut stream, method, path) = self.next_request();
+      info!("API request: {} {}", method, path);
+      match route(&method, &path) {
+        Request::Command(command) => {
+          self.client.replace(Some(stream));
+          break command;
+        },
+        Request::State => respond(&mut stream, "200 OK", &state_json(&self.game.borrow())),
+        Request::Moves =>
+          respond(&mut stream, "200 OK", &format!("{{\"moves\":{}}}", moves_json(&self.game.borrow()))),
+        Request::Invalid(status, reason) => respond(&mut stream, status, &error_json(reason))
+      }
+    }
+  }
+
+  // Unlike the default, answers each command's client, with an error if a move is impossible.
+  fn run_game(&self) {
+    loop {
+      self.view.update();
+      let command = self.receive_command();
+      let is_move = matches!(command, Command::Left | Command::Right | Command::Up | Command::Down);
+      let move_count = self.game().move_count();
+      self.mut_game().execute(command);
+      let quit = matches!(self.game().state(), GameState::Quit);
+      if let Some(mut stream) = self.client.take() {
+        if quit {
+          respond(&mut stream, "200 OK", "{\"quit\":true}");
+        } else if is_move && self.game().move_count() == move_count {
+          respond(&mut stream, "409 Conflict", &error_json("impossible move"));
+        } else {
+          respond(&mut stream, "200 OK", &state_json(&self.game()));
+        }
+      }
+      if quit {
+        break;
+      }
+    }
+  }
+}
+
+// what a request asks for
+enum Request {
+  Command(Command),
+  State,
+  Moves,
+  Invalid(&'static str, &'static str) // the status and the reason
+}
+
+fn route(method: &str, path: &str) -> Request {
+  let command = match path {
+    "/move/left" => Command::Left,
+    "/move/right" => Command::Right,
+    "/move/up" => Command::Up,
+    "/move/down" => Command::Down,
+    "/new" => Command::New,
+    "/quit" => Command::Quit,
+    "/state" if method == "GET" => return Request::State,
+    "/moves" if method == "GET" => return Request::Moves,
+    "/state" | "/moves" => return Request::Invalid("405 Method Not Allowed", "use GET"),
+    _ => return Request::Invalid("404 Not Found", "unknown path")
+  };
+  if method == "POST" {
+    Request::Command(command)
+  } else {
+    Request::Invalid("405 Method Not Allowed", "use POST")
+  }
+}
+
+fn state_json(game: &Game) -> String {
+  let rows: Vec<String> = (0..game.board.size_y())
+    .map(|y| {
+      let row: Vec<String> = (0..game.board.size_x())
+        .map(|x| match game.board.at_xy(x, y) {
+          Square::Empty => String::from("0"),
+          Square::Value(value) => value.to_string()
+        })
+        .collect();
+      format!("[{}]", row.join(","))
+    })
+    .collect();
+  format!("{{\"score\":{},\"state\":\"{}\",\"board\":[{}],\"moves\":{}}}",
+          game.score(),
+          game.state().name(),
+          rows.join(","),
+          moves_json(game))
+}
+
+fn moves_json(game: &Game) -> String {
+  let directions = game.board.possible_directions();
+  let moves: Vec<String> = directions.iter().map(|&direction| direction_json(direction)).collect();
+  format!("[{}]", moves.join(","))
+}
+
+fn direction_json(direction: Direction) -> String { format!("\"{:?}\"", direction).to_lowercase() }
+
+fn error_json(reason: &str) -> String { format!("{{\"error\":\"{}\"}}", reason) }
+
+// the method and the path. Headers and body are skipped.
+fn read_request(mut reader: impl BufRead) -> io::Result<(String, String)> {
+  let mut request_line = String::new();
+  reader.read_line(&mut request_line)?;
+  let mut content_length = 0;
+  loop {
+    let mut header = String::new();
+    if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+      break;
+    }
+    if let Some((name, value)) = header.split_once(':') {
+      if name.eq_ignore_ascii_case("content-length") {
+        content_length = value.trim().parse().unwrap_or(0);
+      }
+    }
+  }
+  io::copy(&mut reader.take(content_length), &mut io::sink())?;
+  let mut words = request_line.split_whitespace();
+  match (words.next(), words.next()) {
+    (Some(method), Some(path)) => Ok((method.to_string(), path.to_string())),
+    _ => Err(io::Error::new(io::ErrorKind::InvalidData, "invalid request line"))
+  }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, json: &str) {
+  let response = format!("HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: \
+                          close\r\n\r\n{}\n",
+                         status,
+                         json.len() + 1,
+                         json);
+  if let Err(e) = stream.write_all(response.as_bytes()) {
+    warn!("Cannot answer a client: {}", e);
+  }
+}
@@ -0,0 +1,29 @@
+use std::io::Cursor;
+
+use super::{read_request, route, state_json, Request};
+use crate::game::{Command, Game, Spawn, SpawnSequence};
+
+#[test]
+fn request_line_without_headers_and_body() {
+  let request = "POST /move/left HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\n\r\n{}";
+  assert_eq!(read_request(Cursor::new(request)).unwrap(), (String::from("POST"), String::from("/move/left")));
+  assert!(read_request(Cursor::new("\r\n")).is_err());
+}
+
+#[test]
+fn routes() {
+  assert!(matches!(route("POST", "/move/up"), Request::Command(Command::Up)));
+  assert!(matches!(route("POST", "/new"), Request::Command(Command::New)));
+  assert!(matches!(route("GET", "/state"), Request::State));
+  assert!(matches!(route("GET", "/moves"), Request::Moves));
+  assert!(matches!(route("GET", "/move/up"), Request::Invalid("405 Method Not Allowed", _)));
+  assert!(matches!(route("POST", "/state"), Request::Invalid("405 Method Not Allowed", _)));
+  assert!(matches!(route("GET", "/nowhere"), Request::Invalid("404 Not Found", _)));
+}
+
+#[test]
+fn state_lists_rows_and_possible_moves() {
+  let game = Game::new(2, 2, Box::new(SpawnSequence::new(vec![Spawn { value: 2, at: Some((1, 0)) }])));
+  assert_eq!(state_json(&game),
+             r#"{"score":0,"state":"running","board":[[0,2],[0,0]],"moves":["left","down"]}"#);
+}
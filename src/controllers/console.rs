@@ -1,68 +1,254 @@
-use console::Key;
-use std::cell::{Ref, RefCell, RefMut};
+use console::Term;
+use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::io;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
 
-use super::Controller;
-use crate::game::{Command, Game};
+use super::keys::{Action, Key, KeyMap, Settings};
+use super::recorder::Recorder;
+use super::{confirmation_question, help_lines, Controller};
+use crate::game::{Command, Game, PowerUp};
+use crate::macros::Macros;
+use crate::signals;
 use crate::views::{ConsoleView, View};
 
+// how often timed games are checked while the player does not move
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+// no power-ups that need squares picked
+const ACTIONS: [Action; 11] = [Action::Left,
+                              Action::Right,
+                              Action::Up,
+                              Action::Down,
+                              Action::Hint,
+                              Action::PowerUp(PowerUp::Shuffle),
+                              Action::New,
+                              Action::Quit,
+                              Action::Help,
+                              Action::Record,
+                              Action::Play];
+
+enum Input {
+  Key(console::Key),
+  Line(String),
+  Error(String)
+}
+
 pub struct ConsoleController<'a> {
-  game: &'a RefCell<Game>,
-  view: ConsoleView<'a>
+  game:     &'a RefCell<Game>,
+  view:     ConsoleView<'a>,
+  key_map:  KeyMap,
+  line_map: KeyMap, // for typed lines, which cannot have arrow keys
+  settings: Settings,
+  mode:     String,
+  recorder: Recorder,
+  requests: Sender<()>,
+  inputs:   Receiver<Input>,
+  reading:  Cell<bool> // a request for input is pending
 }
 
 impl<'a> ConsoleController<'a> {
-  pub fn create(game: &'a RefCell<Game>, view: ConsoleView<'a>) -> ConsoleController<'a> {
-    ConsoleController { game, view }
+  // `mode` is the name of the game's mode, e.g. "classic"
+  pub fn create(game: &'a RefCell<Game>, view: ConsoleView<'a>, key_map: KeyMap, settings: Settings,
+                mode: String)
+                -> ConsoleController<'a> {
+    let (requests, requests_receiver) = mpsc::channel();
+    let (inputs_sender, inputs) = mpsc::channel();
+    thread::spawn(move || read_inputs(requests_receiver, inputs_sender));
+    let line_map = key_map.clone().with_letters_for_moving();
+    ConsoleController { game,
+                        view,
+                        key_map,
+                        line_map,
+                        settings,
+                        mode,
+                        recorder: Recorder::new(),
+                        requests,
+                        inputs,
+                        reading: Cell::new(false) }
   }
-}
 
-impl<'a> Controller for ConsoleController<'a> {
-  fn view(&self) -> &dyn View { &self.view }
+  // the next input. Err(Timeout) after TICK_INTERVAL without input.
+  fn next_input(&self) -> Result<Input, RecvTimeoutError> {
+    if !self.reading.replace(true) && self.requests.send(()).is_err() {
+      return Err(RecvTimeoutError::Disconnected);
+    }
+    let input = self.inputs.recv_timeout(TICK_INTERVAL)?;
+    self.reading.set(false);
+    Ok(input)
+  }
+
+  // None, if interrupted or there is no more input
+  fn wait_for_input(&self) -> Option<Input> {
+    loop {
+      match self.next_input() {
+        _ if signals::interrupted() => break None,
+        Ok(Input::Error(_)) | Err(RecvTimeoutError::Disconnected) => break None,
+        Ok(input) => break Some(input),
+        Err(RecvTimeoutError::Timeout) => ()
+      }
+    }
+  }
+
+  fn confirmed(&self, question: &str) -> bool {
+    print!("{} (y/n)\r\n", question);
+    match self.wait_for_input() {
+      Some(Input::Key(key)) => Key::from_console(&key) == Some(Key::Char('y')),
+      Some(Input::Line(line)) => matches!(line.trim().to_lowercase().as_str(), "y" | "yes"),
+      _ => false
+    }
+  }
 
-  fn game(&self) -> Ref<Game> { self.game.borrow() }
+  // None, if cancelled
+  fn pick_register(&self, question: &str) -> Option<char> {
+    print!("{} (a-z)\r\n", question);
+    let key = match self.wait_for_input()? {
+      Input::Key(key) => Key::from_console(&key),
+      Input::Line(line) => Key::parse(line.trim()),
+      Input::Error(_) => None
+    };
+    match key {
+      Some(Key::Char(register)) if Macros::is_register(register) => Some(register),
+      _ => None
+    }
+  }
+
+  // Starts recording into a register picked by the player, or stops recording
+  fn record(&self) {
+    if let Some(register) = self.recorder.recording() {
+      match self.recorder.stop_recording() {
+        Ok(()) => print!("macro {} recorded\r\n", register),
+        Err(msg) => print!("{}\r\n", msg)
+      }
+    } else if let Some(register) = self.pick_register("Record a macro into") {
+      self.recorder.start_recording(register);
+      print!("recording into {}\r\n", register);
+    }
+  }
 
-  fn mut_game(&self) -> RefMut<Game> { self.game.borrow_mut() }
+  // true, if the playback of a macro picked by the player has started
+  fn play(&self) -> bool {
+    for line in self.recorder.list() {
+      print!("{}\r\n", line);
+    }
+    match self.pick_register("Play the macro in") {
+      Some(register) if self.recorder.play(register) => true,
+      Some(register) => {
+        print!("nothing recorded in {}\r\n", register);
+        false
+      },
+      None => false
+    }
+  }
 
-  fn receive_command(&self) -> Command {
+  // the next move of a macro being played back, one per TICK_INTERVAL. Any input stops the playback.
+  fn played_command(&self) -> Option<Command> {
+    if !self.recorder.is_playing() {
+      return None;
+    }
+    match self.next_input() {
+      Err(RecvTimeoutError::Timeout) if !signals::interrupted() => (),
+      _ => {
+        // the input is dropped, like a key pressed during an animation
+        self.recorder.stop_playing();
+        return None;
+      }
+    }
+    match self.recorder.next_move(&self.game.borrow()) {
+      Ok(command) => command,
+      Err(msg) => {
+        print!("{}\r\n", msg);
+        None
+      }
+    }
+  }
+
+  fn player_command(&self) -> Command {
     loop {
-      match self.view.term().read_key() {
-        Ok(key) => match key {
-          Key::Unknown => {
-            // terminal appears not to be "user attended". Unfortunately, this is the case
-            // for the Eclipse console.
-            let mut line = String::new();
-            match io::stdin().read_line(&mut line) {
-              Ok(_) => {
-                match line.to_lowercase().as_str().trim() {
-                  "w" => break Command::Up,
-                  "a" => break Command::Left,
-                  "s" => break Command::Down,
-                  "d" => break Command::Right,
-                  "n" => break Command::New,
-                  "q" => break Command::Quit,
-                  _ => println!("try W, A, S, D, N(ew), or Q(uit)") // restarts the loop
-                }
-              },
-              Err(msg) => {
-                println!("I/O Error on STDIN: {}", msg);
-                break Command::Quit;
-              }
-            }
-          },
-          Key::ArrowUp => break Command::Up,
-          Key::ArrowLeft => break Command::Left,
-          Key::ArrowDown => break Command::Down,
-          Key::ArrowRight => break Command::Right,
-          Key::Char('n') => break Command::New,
-          Key::Char('q') => break Command::Quit,
-          _ => println!("try arrow keys, N(ew), or Q(uit)") // restarts the loop
-        },
-        Err(msg) => {
+      if signals::interrupted() {
+        break Command::Quit;
+      }
+      if self.game.borrow().is_paused() {
+        let lines = help_lines(&self.game.borrow(), &self.mode, &self.key_map, &ACTIONS);
+        self.view.show_dialog("Paused", &lines);
+        self.wait_for_input();
+        break Command::Resume;
+      }
+      let input = match self.next_input() {
+        Ok(input) => input,
+        Err(RecvTimeoutError::Timeout) if self.game.borrow().tick_due() => break Command::Tick,
+        Err(RecvTimeoutError::Timeout) => continue,
+        Err(RecvTimeoutError::Disconnected) => break Command::Quit
+      };
+      let (key, key_map) = match input {
+        // terminal appears not to be "user attended". Unfortunately, this is the case for the Eclipse
+        // console. The arrow keys are typed as "up", "down", "left" and "right", or as W, A, S and D, unless
+        // other letters move.
+        Input::Line(line) => (Key::parse(line.trim()), &self.line_map),
+        Input::Key(key) => (Key::from_console(&key), &self.key_map),
+        Input::Error(_) if signals::interrupted() => break Command::Quit, // Ctrl-C while reading a key
+        Input::Error(msg) => {
           println!("I/O Error on STDIN: {}", msg);
           break Command::Quit;
         }
+      };
+      match key.and_then(|key| key_map.action(key)).filter(|action| ACTIONS.contains(action)) {
+        Some(Action::Record) => self.record(),
+        Some(Action::Play) =>
+          if self.play() {
+            break Command::Nop;
+          },
+        Some(action) =>
+          if let Some(command) = action.command() {
+            match confirmation_question(&command, &self.game.borrow(), self.settings) {
+              Some(question) if !self.confirmed(question) => print!("cancelled\r\n"),
+              _ => break command,
+            }
+          },
+        None => print!("try {}\r\n", self.key_map.help(&ACTIONS)) // restarts the loop
       }
     }
   }
 }
+
+impl<'a> Controller for ConsoleController<'a> {
+  fn view(&self) -> &dyn View { &self.view }
+
+  fn game(&self) -> Ref<'_, Game> { self.game.borrow() }
+
+  fn mut_game(&self) -> RefMut<'_, Game> { self.game.borrow_mut() }
+
+  fn receive_command(&self) -> Command {
+    if let Some(command) = self.played_command() {
+      return command;
+    }
+    let command = self.player_command();
+    self.recorder.record(&command, &self.game.borrow());
+    command
+  }
+}
+
+// Reads a key, or a line if the terminal is not attended, per request of the controller. Reading blocks,
+// so it happens in a thread of its own, which lets the controller send ticks in the meantime. Reading on
+// request only ensures that the terminal is not left in raw mode when the game ends.
+fn read_inputs(requests: Receiver<()>, inputs: Sender<Input>) {
+  let term = Term::stdout();
+  while requests.recv().is_ok() {
+    let input = match term.read_key() {
+      Ok(console::Key::Unknown) => {
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+          Ok(0) => Input::Error(String::from("end of input")),
+          Ok(_) => Input::Line(line),
+          Err(msg) => Input::Error(msg.to_string())
+        }
+      },
+      Ok(key) => Input::Key(key),
+      Err(msg) => Input::Error(msg.to_string())
+    };
+    let is_error = matches!(input, Input::Error(_));
+    if inputs.send(input).is_err() || is_error {
+      break; // the controller is gone, or there is nothing more to read
+    }
+  }
+}
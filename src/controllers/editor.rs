@@ -0,0 +1,169 @@
+use ncurses as nc;
+use std::cell::RefCell;
+
+use crate::game::{Board, Game, Square};
+use crate::puzzle::{self, Puzzle};
+use crate::savegame::SaveGame;
+use crate::signals;
+use crate::views::{NCursesView, Selection, View};
+
+const KEY_ESCAPE: i32 = 27;
+const HELP: &str = "value+Enter, . clears | P(lay), S(ave puzzle), G (save game), Q(uit)";
+const EMPTY_BOARD: &str = "Put a tile on the board first"; // no shift could change an empty board
+
+// how the player left the editor
+pub enum EditorExit {
+  Play, // from the edited board, which is the game's start board now
+  Quit
+}
+
+// Lets the player put tiles on the board of `game` (ncurses only). The board is edited in place.
+pub struct BoardEditor<'a> {
+  game: &'a RefCell<Game>,
+  view: &'a NCursesView<'a>,
+  cursor: RefCell<(usize, usize)>,
+  message: RefCell<String> // shown instead of the help until the next key
+}
+
+impl<'a> BoardEditor<'a> {
+  pub fn create(game: &'a RefCell<Game>, view: &'a NCursesView<'a>) -> BoardEditor<'a> {
+    nc::cbreak();
+    nc::keypad(nc::stdscr(), true);
+    nc::noecho();
+    BoardEditor { game, view, cursor: RefCell::new((0, 0)), message: RefCell::new(String::new()) }
+  }
+
+  pub fn run(&self) -> EditorExit {
+    let mut typed = String::new(); // digits of the value being typed
+    let exit = loop {
+      let prompt = if !typed.is_empty() {
+        format!("Value: {}_", typed)
+      } else if !self.message.borrow().is_empty() {
+        self.message.replace(String::new())
+      } else {
+        String::from(HELP)
+      };
+      self.show(&prompt);
+      let key = nc::getch();
+      if signals::interrupted() {
+        break EditorExit::Quit;
+      }
+      let (size_x, size_y) = (self.board().size_x(), self.board().size_y());
+      let (x, y) = *self.cursor.borrow();
+      match key {
+        nc::KEY_LEFT => self.move_cursor(x.saturating_sub(1), y),
+        nc::KEY_RIGHT => self.move_cursor((x + 1).min(size_x - 1), y),
+        nc::KEY_UP => self.move_cursor(x, y.saturating_sub(1)),
+        nc::KEY_DOWN => self.move_cursor(x, (y + 1).min(size_y - 1)),
+        nc::KEY_DC | nc::KEY_BACKSPACE => self.put((x, y), Square::Empty),
+        KEY_ESCAPE => typed.clear(),
+        nc::KEY_ENTER => self.put_typed((x, y), &mut typed),
+        _ => match key as u8 as char {
+          digit @ '0'..='9' => typed.push(digit),
+          '\n' | ' ' => self.put_typed((x, y), &mut typed),
+          '.' | 'x' | 'X' => self.put((x, y), Square::Empty),
+          'p' | 'P' if self.board().max_tile().is_none() => self.set_message(String::from(EMPTY_BOARD)),
+          'p' | 'P' => break EditorExit::Play,
+          's' | 'S' => self.save_puzzle(),
+          'g' | 'G' => self.save_game(),
+          'q' | 'Q' => break EditorExit::Quit,
+          _ => ()
+        }
+      }
+    };
+    self.view.set_selection(None);
+    if let EditorExit::Play = exit {
+      let board = self.board();
+      self.game.borrow_mut().start_from(board, 0);
+    }
+    exit
+  }
+
+  fn board(&self) -> Board { self.game.borrow().board.clone() }
+
+  fn show(&self, prompt: &str) {
+    let (x, y) = *self.cursor.borrow();
+    let cursor = self.board().coord(x, y);
+    self.view.set_selection(Some(Selection { cursor, marked: None, prompt: prompt.to_string() }));
+    self.view.update();
+  }
+
+  fn move_cursor(&self, x: usize, y: usize) { self.cursor.replace((x, y)); }
+
+  fn put(&self, (x, y): (usize, usize), square: Square) {
+    let mut board = self.board();
+    board.put(board.coord(x, y), square);
+    self.game.borrow_mut().set_board(board);
+  }
+
+  // 0 clears the square
+  fn put_typed(&self, at: (usize, usize), typed: &mut String) {
+    match typed.as_str() {
+      "" => (),
+      "0" => self.put(at, Square::Empty),
+      value => match puzzle::parse_tile(value) {
+        Some(value) => self.put(at, Square::Value(value)),
+        None => self.set_message(format!("{} is not a power of two", value))
+      }
+    }
+    typed.clear();
+  }
+
+  fn save_puzzle(&self) {
+    let path = match self.ask("Save puzzle as: ") {
+      Some(path) => path,
+      None => return
+    };
+    let max_tile = self.board().max_tile().map_or(2, |(_, value)| value);
+    let goal = match self.ask(&format!("Goal tile (Enter: {}): ", 2 * max_tile)) {
+      Some(goal) if goal.is_empty() => 2 * max_tile,
+      Some(goal) => match puzzle::parse_tile(&goal) {
+        Some(goal) => goal,
+        None => return self.set_message(format!("Invalid goal tile \"{}\"", goal))
+      },
+      None => return
+    };
+    let puzzle = Puzzle { board: self.board(), spawns: vec![], goal, move_limit: None };
+    self.report(puzzle.save(&path), &path);
+  }
+
+  fn save_game(&self) {
+    if self.board().max_tile().is_none() {
+      return self.set_message(String::from(EMPTY_BOARD));
+    }
+    if let Some(path) = self.ask("Save game as: ") {
+      let save_game = SaveGame { board: self.board(), score: 0 };
+      self.report(save_game.save(&path), &path);
+    }
+  }
+
+  fn report(&self, result: Result<(), String>, path: &str) {
+    match result {
+      Ok(()) => self.set_message(format!("Saved to {}", path)),
+      Err(msg) => self.set_message(msg)
+    }
+  }
+
+  fn set_message(&self, message: String) { self.message.replace(message); }
+
+  // a line of text typed by the player. None, if cancelled with Esc
+  fn ask(&self, question: &str) -> Option<String> {
+    let mut answer = String::new();
+    loop {
+      self.show(&format!("{}{}_", question, answer));
+      match nc::getch() {
+        _ if signals::interrupted() => return None,
+        nc::KEY_ENTER => return Some(answer),
+        KEY_ESCAPE => return None,
+        nc::KEY_BACKSPACE | 127 | 8 => {
+          answer.pop();
+        },
+        key => match key as u8 as char {
+          '\n' => return Some(answer),
+          c if (' '..='~').contains(&c) => answer.push(c),
+          _ => ()
+        }
+      }
+    }
+  }
+}
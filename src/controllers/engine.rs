@@ -0,0 +1,109 @@
+// A line-based protocol for programs playing the game, e.g. bots. They write commands to stdin, one per
+// line, and read the state of the game from stdout (see views/engine.rs):
+//
+//   move left       (also right, up, down)
+//   undo            (takes back the latest move)
+//   new             (starts a new game)
+//   seed 42         (starts a new game with the same tiles each time)
+//   state           (prints the state only)
+//   quit
+//
+// Each command is answered with the state of the game, or with a single line "error <reason>".
+
+#[cfg(test)]
+mod test;
+
+use std::cell::{Ref, RefCell, RefMut};
+use std::io::{self, BufRead};
+
+use super::Controller;
+use crate::game::{Command, Game, GameState, Randomizer};
+use crate::views::{EngineView, View};
+
+const GREETING: &str = "2048 engine";
+
+pub struct EngineController<'a> {
+  game: &'a RefCell<Game>,
+  view: EngineView<'a>
+}
+
+impl<'a> EngineController<'a> {
+  pub fn create(game: &'a RefCell<Game>, view: EngineView<'a>) -> EngineController<'a> {
+    EngineController { game, view }
+  }
+
+  // Executes `command`. Err with the reason, if it has no effect.
+  fn execute(&self, command: Command) -> Result<(), &'static str> {
+    let error = match command {
+      Command::Left | Command::Right | Command::Up | Command::Down => Some("illegal move"),
+      Command::Undo => Some("nothing to undo"),
+      _ => None,
+    };
+    let move_count = self.game().move_count();
+    self.mut_game().execute(command);
+    match error {
+      Some(error) if self.game().move_count() == move_count => Err(error),
+      _ => Ok(()),
+    }
+  }
+
+  fn parse(&self, line: &str) -> Result<Command, String> {
+    match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+      ["move", "left"] => Ok(Command::Left),
+      ["move", "right"] => Ok(Command::Right),
+      ["move", "up"] => Ok(Command::Up),
+      ["move", "down"] => Ok(Command::Down),
+      ["move", direction] => Err(format!("unknown direction \"{}\"", direction)),
+      ["undo"] => Ok(Command::Undo),
+      ["new"] => Ok(Command::New),
+      ["seed", seed] => {
+        let seed = seed.parse().map_err(|_| format!("invalid seed \"{}\"", seed))?;
+        self.game.borrow_mut().set_spawner(Box::new(Randomizer::with_seed(seed)));
+        Ok(Command::Nop)
+      },
+      ["state"] => Ok(Command::Nop),
+      ["quit"] => Ok(Command::Quit),
+      _ => Err(format!("unknown command \"{}\"", line.trim())),
+    }
+  }
+}
+
+impl<'a> Controller for EngineController<'a> {
+  fn view(&self) -> &dyn View { &self.view }
+
+  fn game(&self) -> Ref<'_, Game> { self.game.borrow() }
+
+  fn mut_game(&self) -> RefMut<'_, Game> { self.game.borrow_mut() }
+
+  // the next valid command. Quit at the end of input.
+  fn receive_command(&self) -> Command {
+    let stdin = io::stdin();
+    loop {
+      let mut line = String::new();
+      match stdin.lock().read_line(&mut line) {
+        Ok(0) | Err(_) => break Command::Quit,
+        Ok(_) if line.trim().is_empty() => (),
+        Ok(_) => match self.parse(&line) {
+          Ok(command) => break command,
+          Err(msg) => println!("error {}", msg),
+        },
+      }
+    }
+  }
+
+  // Unlike the default, prints an error instead of the state if a command has no effect.
+  fn run_game(&self) {
+    println!("{}", GREETING);
+    self.view.update();
+    loop {
+      let result = self.execute(self.receive_command());
+      if let GameState::Quit = self.game().state() {
+        break;
+      }
+      match result {
+        Err(error) => println!("error {}", error),
+        Ok(()) => self.view.update(),
+      }
+    }
+  }
+}
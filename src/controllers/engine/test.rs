@@ -0,0 +1,32 @@
+use std::cell::RefCell;
+
+use super::EngineController;
+use crate::game::{Command, Game, Spawn, SpawnSequence};
+use crate::views::EngineView;
+
+fn game() -> RefCell<Game> {
+  RefCell::new(Game::new(2, 2, Box::new(SpawnSequence::new(vec![Spawn { value: 2, at: Some((0, 0)) }]))))
+}
+
+#[test]
+fn parse_commands() {
+  let game = game();
+  let engine = EngineController::create(&game, EngineView::new(&game));
+  assert!(matches!(engine.parse("move left\n"), Ok(Command::Left)));
+  assert!(matches!(engine.parse("  undo "), Ok(Command::Undo)));
+  assert!(matches!(engine.parse("state"), Ok(Command::Nop)));
+  assert_eq!(engine.parse("move sideways").unwrap_err(), "unknown direction \"sideways\"");
+  assert_eq!(engine.parse("seed x").unwrap_err(), "invalid seed \"x\"");
+  assert_eq!(engine.parse("jump").unwrap_err(), "unknown command \"jump\"");
+}
+
+#[test]
+fn errors_and_state() {
+  let game = game();
+  let engine = EngineController::create(&game, EngineView::new(&game));
+  assert_eq!(engine.execute(Command::Left), Err("illegal move"));
+  assert_eq!(engine.execute(Command::Undo), Err("nothing to undo"));
+  assert_eq!(engine.execute(Command::Right), Ok(()));
+  assert_eq!(EngineView::state(&game.borrow()),
+             "score 0\nboard\n2 2\n. .\nstate running\nmoves left right down\nok\n");
+}
@@ -0,0 +1,294 @@
+// Key bindings shared by all controllers. A preset ("arrows", "wasd" or "vim") can be adjusted in a config
+// file, by default ~/.game2048_keys, which also holds other settings:
+//
+//   # comment
+//   preset vim          (optional, the first line. Default: arrows)
+//   hint t i            (an action and its keys, which replace the preset's keys for the action)
+//   confirm off         (a setting: no confirmation before a new game or quitting throws away a game in
+//                       progress)
+//
+// Actions: left, right, up, down, hint, swap, delete, shuffle, new, quit, help, record, play. Keys: up, down,
+// left, right (the arrow keys), enter, esc, space, or a single character. Letters are case-insensitive.
+
+#[cfg(test)]
+mod test;
+
+use ncurses as nc;
+use std::fs;
+
+use crate::game::{Command, Direction, PowerUp};
+use crate::home_path;
+
+const CONFIG_FILE_NAME: &str = ".game2048_keys";
+const DEFAULT_PRESET: &str = "arrows";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Key {
+  Up, // arrow keys
+  Down,
+  Left,
+  Right,
+  Enter,
+  Escape,
+  Char(char) // lower case
+}
+
+impl Key {
+  // None for keys without a name, e.g. function keys
+  pub fn from_ncurses(code: i32) -> Option<Key> {
+    match code {
+      nc::KEY_UP => Some(Key::Up),
+      nc::KEY_DOWN => Some(Key::Down),
+      nc::KEY_LEFT => Some(Key::Left),
+      nc::KEY_RIGHT => Some(Key::Right),
+      nc::KEY_ENTER | 10 | 13 => Some(Key::Enter),
+      27 => Some(Key::Escape),
+      32..=126 => Some(Key::Char((code as u8 as char).to_ascii_lowercase())),
+      _ => None
+    }
+  }
+
+  pub fn from_console(key: &console::Key) -> Option<Key> {
+    match key {
+      console::Key::ArrowUp => Some(Key::Up),
+      console::Key::ArrowDown => Some(Key::Down),
+      console::Key::ArrowLeft => Some(Key::Left),
+      console::Key::ArrowRight => Some(Key::Right),
+      console::Key::Enter => Some(Key::Enter),
+      console::Key::Escape => Some(Key::Escape),
+      console::Key::Char(c) if !c.is_control() => Some(Key::Char(c.to_ascii_lowercase())),
+      _ => None
+    }
+  }
+
+  pub fn parse(name: &str) -> Option<Key> {
+    match name.to_lowercase().as_str() {
+      "up" => Some(Key::Up),
+      "down" => Some(Key::Down),
+      "left" => Some(Key::Left),
+      "right" => Some(Key::Right),
+      "enter" => Some(Key::Enter),
+      "esc" => Some(Key::Escape),
+      "space" => Some(Key::Char(' ')),
+      name => {
+        let mut chars = name.chars();
+        match (chars.next(), chars.next()) {
+          (Some(c), None) => Some(Key::Char(c)),
+          _ => None
+        }
+      }
+    }
+  }
+
+  pub fn name(self) -> String {
+    match self {
+      Key::Up => String::from("↑"),
+      Key::Down => String::from("↓"),
+      Key::Left => String::from("←"),
+      Key::Right => String::from("→"),
+      Key::Enter => String::from("Enter"),
+      Key::Escape => String::from("Esc"),
+      Key::Char(' ') => String::from("Space"),
+      Key::Char(c) => c.to_string()
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+  Left,
+  Right,
+  Up,
+  Down,
+  Hint,
+  PowerUp(PowerUp),
+  New,
+  Quit,
+  Help,   // shows the keys and rules while the game is paused
+  Record, // starts or stops recording a macro into a register
+  Play    // plays back the macro in a register
+}
+
+impl Action {
+  pub const ALL: [Action; 13] = [Action::Left,
+                                 Action::Right,
+                                 Action::Up,
+                                 Action::Down,
+                                 Action::Hint,
+                                 Action::PowerUp(PowerUp::Swap),
+                                 Action::PowerUp(PowerUp::Delete),
+                                 Action::PowerUp(PowerUp::Shuffle),
+                                 Action::New,
+                                 Action::Quit,
+                                 Action::Help,
+                                 Action::Record,
+                                 Action::Play];
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Action::Left => "left",
+      Action::Right => "right",
+      Action::Up => "up",
+      Action::Down => "down",
+      Action::Hint => "hint",
+      Action::PowerUp(power_up) => power_up.name(),
+      Action::New => "new",
+      Action::Quit => "quit",
+      Action::Help => "help",
+      Action::Record => "record",
+      Action::Play => "play"
+    }
+  }
+
+  pub fn direction(self) -> Option<Direction> {
+    match self {
+      Action::Left => Some(Direction::Left),
+      Action::Right => Some(Direction::Right),
+      Action::Up => Some(Direction::Up),
+      Action::Down => Some(Direction::Down),
+      _ => None
+    }
+  }
+
+  // None for power-ups that need squares picked by the player, and for macros, which need a register
+  pub fn command(self) -> Option<Command> {
+    match self {
+      Action::Left | Action::Right | Action::Up | Action::Down => self.direction().map(Command::from),
+      Action::Hint => Some(Command::Hint),
+      Action::PowerUp(PowerUp::Shuffle) => Some(Command::Shuffle),
+      Action::PowerUp(_) => None,
+      Action::New => Some(Command::New),
+      Action::Quit => Some(Command::Quit),
+      Action::Help => Some(Command::Pause),
+      Action::Record | Action::Play => None
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct KeyMap {
+  bindings: Vec<(Key, Action)>
+}
+
+// the preferences in the config file that are not key bindings
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+  pub confirm: bool // whether new games and quitting need to be confirmed while a game is in progress
+}
+
+impl Default for Settings {
+  fn default() -> Settings { Settings { confirm: true } }
+}
+
+impl KeyMap {
+  pub const PRESETS: [&'static str; 3] = ["arrows", "wasd", "vim"];
+  const MOVES: [Action; 4] = [Action::Up, Action::Left, Action::Down, Action::Right];
+
+  pub fn preset(name: &str) -> Option<KeyMap> {
+    // the keys of the last actions in Action::ALL. The arrow keys work in all presets.
+    let keys = match name {
+      "arrows" => "hsxrnqpm@",
+      "wasd" => "adwshexrnqpm@",
+      "vim" => "hlkjtsxrnqpm@",
+      _ => return None
+    };
+    let mut bindings: Vec<(Key, Action)> =
+      [Key::Left, Key::Right, Key::Up, Key::Down].iter().copied().zip(Action::ALL.iter().copied()).collect();
+    let actions = Action::ALL.iter().skip(Action::ALL.len() - keys.len()).copied();
+    bindings.extend(keys.chars().map(Key::Char).zip(actions));
+    bindings.push((Key::Char('?'), Action::Help));
+    Some(KeyMap { bindings })
+  }
+
+  // A preset by name, or the config file at `preset_or_path`. Without either, the default config file if
+  // it exists, else the default preset. Presets come with the default settings.
+  pub fn find(preset_or_path: Option<&str>) -> Result<(KeyMap, Settings), String> {
+    let preset = |name| Self::preset(name).map(|key_map| (key_map, Settings::default()));
+    match preset_or_path {
+      Some(name) => preset(name).map_or_else(|| Self::load(name), Ok),
+      None => match home_path(CONFIG_FILE_NAME) {
+        Ok(path) if path.exists() => Self::load(&path.to_string_lossy()),
+        _ => Ok(preset(DEFAULT_PRESET).unwrap())
+      }
+    }
+  }
+
+  pub fn load(path: &str) -> Result<(KeyMap, Settings), String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    Self::parse(&text).map_err(|e| format!("{}: {}", path, e))
+  }
+
+  pub fn parse(text: &str) -> Result<(KeyMap, Settings), String> {
+    let mut key_map = Self::preset(DEFAULT_PRESET).unwrap();
+    let mut settings = Settings::default();
+    for (line_index, line) in text.lines().enumerate() {
+      let error = |msg: String| format!("line {}: {}", line_index + 1, msg);
+      match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [] => (),
+        [comment, ..] if comment.starts_with('#') => (),
+        ["preset", name] if line_index == 0 =>
+          key_map = Self::preset(name).ok_or_else(|| error(format!("unknown preset \"{}\"", name)))?,
+        ["confirm", "on"] => settings.confirm = true,
+        ["confirm", "off"] => settings.confirm = false,
+        [action, keys @ ..] => {
+          let action = Action::ALL.iter()
+                                  .copied()
+                                  .find(|a| a.name() == *action)
+                                  .ok_or_else(|| error(format!("unknown action \"{}\"", action)))?;
+          let key = |name: &&str| Key::parse(name).ok_or_else(|| error(format!("unknown key \"{}\"", name)));
+          let keys = keys.iter().map(key).collect::<Result<Vec<_>, _>>()?;
+          key_map.bind(action, &keys);
+        }
+      }
+    }
+    Ok((key_map, settings))
+  }
+
+  // replaces the keys of `action`. The keys are taken from other actions.
+  fn bind(&mut self, action: Action, keys: &[Key]) {
+    self.bindings.retain(|(key, bound)| *bound != action && !keys.contains(key));
+    self.bindings.extend(keys.iter().map(|&key| (key, action)));
+  }
+
+  // For two players on one keyboard: one moves with letters, the other one with the arrow keys. Without
+  // letters for moving, W, A, S and D are taken.
+  pub fn with_letters_for_moving(mut self) -> KeyMap {
+    if self.letters_for_moving().is_empty() {
+      for (&action, letter) in Self::MOVES.iter().zip("wasd".chars()) {
+        self.bindings.retain(|(key, _)| *key != Key::Char(letter));
+        self.bindings.push((Key::Char(letter), action));
+      }
+    }
+    self
+  }
+
+  // e.g. "WASD": the first letter for up, left, down and right, as far as bound
+  pub fn letters_for_moving(&self) -> String {
+    let letter = |&action| {
+      self.keys(action).into_iter().find_map(|key| if let Key::Char(c) = key { Some(c) } else { None })
+    };
+    Self::MOVES.iter().filter_map(letter).collect::<String>().to_uppercase()
+  }
+
+  pub fn action(&self, key: Key) -> Option<Action> {
+    self.bindings.iter().find(|(bound, _)| *bound == key).map(|&(_, action)| action)
+  }
+
+  pub fn keys(&self, action: Action) -> Vec<Key> {
+    self.bindings.iter().filter(|(_, bound)| *bound == action).map(|&(key, _)| key).collect()
+  }
+
+  // e.g. "left: ←/a, right: →/d"
+  pub fn help(&self, actions: &[Action]) -> String { self.help_lines(actions).join(", ") }
+
+  // e.g. ["left: ←/a", "right: →/d"]
+  pub fn help_lines(&self, actions: &[Action]) -> Vec<String> {
+    actions
+      .iter()
+      .map(|&action| {
+        let keys: Vec<String> = self.keys(action).into_iter().map(Key::name).collect();
+        format!("{}: {}", action.name(), keys.join("/"))
+      })
+      .collect()
+  }
+}
@@ -0,0 +1,33 @@
+use super::{Action, Key, KeyMap, Settings};
+use crate::game::PowerUp;
+
+#[test]
+fn presets_keep_the_arrow_keys() {
+  for name in KeyMap::PRESETS.iter() {
+    let key_map = KeyMap::preset(name).unwrap();
+    assert_eq!(key_map.action(Key::Left), Some(Action::Left));
+    assert_eq!(key_map.action(Key::Char('q')), Some(Action::Quit));
+  }
+  assert_eq!(KeyMap::preset("vim").unwrap().action(Key::Char('j')), Some(Action::Down));
+  assert_eq!(KeyMap::preset("wasd").unwrap().action(Key::Char('s')), Some(Action::Down));
+}
+
+#[test]
+fn config_file_replaces_keys_of_preset() {
+  let key_map = KeyMap::parse("preset wasd\n# comment\nswap s\n").unwrap().0;
+  assert_eq!(key_map.action(Key::Char('s')), Some(Action::PowerUp(PowerUp::Swap)));
+  assert_eq!(key_map.action(Key::Char('e')), None);
+  assert_eq!(key_map.keys(Action::Down), vec![Key::Down]);
+}
+
+#[test]
+fn config_file_errors_name_the_line() {
+  assert_eq!(KeyMap::parse("hint t\njump j").unwrap_err(), "line 2: unknown action \"jump\"");
+  assert!(KeyMap::parse("hint F1").is_err());
+}
+
+#[test]
+fn config_file_can_turn_off_confirmation() {
+  assert_eq!(KeyMap::parse("hint t").unwrap().1, Settings { confirm: true });
+  assert_eq!(KeyMap::parse("preset vim\nconfirm off").unwrap().1, Settings { confirm: false });
+}
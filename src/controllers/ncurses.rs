@@ -1,47 +1,305 @@
+#[cfg(test)]
+mod test;
+
 use ncurses as nc;
-use std::cell::{Ref, RefCell, RefMut};
+use std::cell::{Cell, Ref, RefCell, RefMut};
+
+use super::keys::{Action, Key, KeyMap, Settings};
+use super::recorder::Recorder;
+use super::{confirmation_question, help_lines, Controller};
+use crate::game::{Command, Coord, Direction, Game, GameState, PowerUp};
+use crate::macros::Macros;
+use crate::signals;
+use crate::views::{NCursesView, Selection, View};
 
-use super::Controller;
-use crate::game::{Command, Game};
-use crate::views::{NCursesView, View};
+const TICK_MILLIS: i32 = 250; // how often timed games are updated while the player does not move
 
 pub struct NCursesController<'a> {
-  game: &'a RefCell<Game>,
-  view: NCursesView<'a>
+  game:       &'a RefCell<Game>,
+  view:       NCursesView<'a>,
+  key_map:    KeyMap,
+  settings:   Settings,
+  mode:       String,
+  recorder:   Recorder,
+  pressed_at: Cell<Option<(i32, i32)>> // where the mouse button went down: line and column on the screen
 }
 
 impl<'a> NCursesController<'a> {
-  pub fn create(game: &'a RefCell<Game>, view: NCursesView<'a>) -> NCursesController<'a> {
+  // `mode` is the name of the game's mode, e.g. "classic"
+  pub fn create(game: &'a RefCell<Game>, view: NCursesView<'a>, key_map: KeyMap, settings: Settings,
+                mode: String)
+                -> NCursesController<'a> {
     nc::cbreak();
     nc::keypad(nc::stdscr(), true);
     nc::noecho();
-    NCursesController { game, view }
+    nc::timeout(TICK_MILLIS);
+    nc::mousemask((nc::BUTTON1_PRESSED | nc::BUTTON1_RELEASED) as nc::mmask_t, None);
+    nc::mouseinterval(0); // report pressing and releasing separately, not as a click
+    NCursesController { game,
+                        view,
+                        key_map,
+                        settings,
+                        mode,
+                        recorder: Recorder::new(),
+                        pressed_at: Cell::new(None) }
   }
-}
 
-impl<'a> Controller for NCursesController<'a> {
-  fn view(&self) -> &dyn View { &self.view }
+  // Dragging the mouse moves in the direction of the drag. Clicking on the board moves towards the edge
+  // nearest to the click, unless it is near the center.
+  fn mouse_direction(&self) -> Option<Direction> {
+    let mut event = nc::MEVENT { id: 0, x: 0, y: 0, z: 0, bstate: 0 };
+    if nc::getmouse(&mut event) != nc::OK {
+      return None;
+    }
+    if event.bstate & nc::BUTTON1_PRESSED as nc::mmask_t != 0 {
+      self.pressed_at.set(Some((event.y, event.x)));
+      return None;
+    }
+    if event.bstate & nc::BUTTON1_RELEASED as nc::mmask_t == 0 {
+      return None;
+    }
+    match self.pressed_at.take() {
+      // a character is about twice as high as wide
+      Some((y, x)) if (y, x) != (event.y, event.x) =>
+        main_direction((event.y - y) as f32, (event.x - x) as f32 / 2.0, 1.0),
+      _ => {
+        let (top, left, height, width) = self.view.board_box()?;
+        if event.y < top || event.y >= top + height || event.x < left || event.x >= left + width {
+          return None;
+        }
+        let (half_height, half_width) = (height as f32 / 2.0, width as f32 / 2.0);
+        let dy = (event.y - top) as f32 + 0.5 - half_height;
+        let dx = (event.x - left) as f32 + 0.5 - half_width;
+        main_direction(dy / half_height, dx / half_width, 0.5)
+      },
+    }
+  }
 
-  fn game(&self) -> Ref<Game> { self.game.borrow() }
+  // None, if the power-up is used up or picking a square is cancelled
+  fn power_up_command(&self, power_up: PowerUp) -> Option<Command> {
+    if self.game.borrow().power_ups(power_up) == 0 {
+      nc::beep();
+      return None;
+    }
+    match power_up {
+      PowerUp::Swap => {
+        let first = self.pick_square("Swap: pick a tile (Enter), Esc cancels", None)?;
+        let second = self.pick_square("Swap: pick the other tile (Enter), Esc cancels", Some(first))?;
+        Some(Command::Swap(first, second))
+      },
+      PowerUp::Delete =>
+        self.pick_square("Delete: pick a tile (Enter), Esc cancels", None).map(Command::Delete),
+      PowerUp::Shuffle => Some(Command::Shuffle),
+    }
+  }
 
-  fn mut_game(&self) -> RefMut<Game> { self.game.borrow_mut() }
+  fn confirmed(&self, question: &str) -> bool {
+    let key = self.show_dialog(question, &[String::from("y: yes, any other key: no")]);
+    key.and_then(Key::from_ncurses) == Some(Key::Char('y'))
+  }
 
-  fn receive_command(&self) -> Command {
+  // shows a dialog over the board until a key is pressed. None, if interrupted.
+  fn show_dialog(&self, title: &str, lines: &[String]) -> Option<i32> {
+    self.view.set_dialog(title, lines);
+    let key = loop {
+      self.view.update();
+      match nc::getch() {
+        _ if signals::interrupted() => break None,
+        nc::ERR | nc::KEY_RESIZE | nc::KEY_MOUSE => (),
+        key => break Some(key)
+      }
+    };
+    self.view.clear_dialog();
+    self.view.update();
+    key
+  }
+
+  // Lets the player move a cursor over the board with the direction keys. None, if cancelled.
+  fn pick_square(&self, prompt: &str, marked: Option<Coord>) -> Option<Coord> {
+    let (size_x, size_y) = (self.game.borrow().board.size_x(), self.game.borrow().board.size_y());
+    let (mut x, mut y) = marked.map_or((0, 0), |at| (at.x, at.y));
+    let picked = loop {
+      let cursor = self.game.borrow().board.coord(x, y);
+      self.view.set_selection(Some(Selection { cursor, marked, prompt: prompt.to_string() }));
+      self.view.update();
+      let key = Key::from_ncurses(nc::getch());
+      if signals::interrupted() {
+        break None;
+      }
+      match key {
+        Some(Key::Enter) | Some(Key::Char(' ')) => break Some(cursor),
+        Some(Key::Escape) => break None,
+        _ => (),
+      }
+      match key.and_then(|key| self.key_map.action(key)) {
+        Some(Action::Left) => x = x.saturating_sub(1),
+        Some(Action::Right) => x = (x + 1).min(size_x - 1),
+        Some(Action::Up) => y = y.saturating_sub(1),
+        Some(Action::Down) => y = (y + 1).min(size_y - 1),
+        Some(Action::Quit) => break None,
+        _ => (),
+      }
+    };
+    self.view.set_selection(None);
+    picked
+  }
+
+  // Starts recording into a register picked by the player, or stops recording. Nop for showing the change.
+  fn record_command(&self) -> Option<Command> {
+    if self.recorder.recording().is_some() {
+      if let Err(msg) = self.recorder.stop_recording() {
+        self.show_dialog("Error", &[msg]);
+      }
+    } else {
+      self.recorder.start_recording(self.pick_register("Record a macro", vec![])?);
+    }
+    self.show_recording();
+    Some(Command::Nop)
+  }
+
+  fn show_recording(&self) {
+    let caption = self.recorder.recording().map_or(String::new(), |register| format!("REC {}", register));
+    self.view.set_caption(&caption);
+  }
+
+  // Nop, if the playback of a macro picked by the player has started
+  fn play_command(&self) -> Option<Command> {
+    let register = self.pick_register("Play a macro", self.recorder.list())?;
+    if self.recorder.play(register) {
+      Some(Command::Nop)
+    } else {
+      nc::beep();
+      None
+    }
+  }
+
+  // None, if cancelled
+  fn pick_register(&self, title: &str, mut lines: Vec<String>) -> Option<char> {
+    lines.push(String::from("a-z: register, other keys cancel"));
+    match self.show_dialog(title, &lines).and_then(Key::from_ncurses) {
+      Some(Key::Char(register)) if Macros::is_register(register) => Some(register),
+      _ => None
+    }
+  }
+
+  // the next move of a macro being played back, once the previous one is shown. Any key stops the
+  // playback.
+  fn played_command(&self) -> Option<Command> {
+    if !self.recorder.is_playing() {
+      return None;
+    }
+    let key = getch_animating(&[&self.view], 0);
+    nc::timeout(TICK_MILLIS);
+    if key != nc::ERR || signals::interrupted() {
+      self.recorder.stop_playing();
+      return None;
+    }
+    match self.recorder.next_move(&self.game.borrow()) {
+      Ok(command) => command,
+      Err(msg) => {
+        nc::beep();
+        self.view.set_caption(&msg);
+        Some(Command::Nop)
+      }
+    }
+  }
+
+  fn player_command(&self) -> Command {
+    self.show_recording(); // replaces messages about the latest playback
     loop {
-      let key = nc::getch();
+      if signals::interrupted() {
+        break Command::Quit;
+      }
+      if self.game.borrow().is_paused() {
+        let lines = help_lines(&self.game.borrow(), &self.mode, &self.key_map, &Action::ALL);
+        self.show_dialog("Paused", &lines);
+        break Command::Resume;
+      }
+      let key = getch_animating(&[&self.view], TICK_MILLIS);
       match key {
+        nc::ERR if self.game.borrow().is_timed() => break Command::Tick, // timeout
         nc::KEY_RESIZE => break Command::Nop, // window resize event
-        nc::KEY_LEFT => break Command::Left,
-        nc::KEY_RIGHT => break Command::Right,
-        nc::KEY_UP => break Command::Up,
-        nc::KEY_DOWN => break Command::Down,
+        nc::KEY_MOUSE =>
+          if let Some(direction) = self.mouse_direction() {
+            break Command::from(direction);
+          },
         _ => ()
       }
-      match key as u8 as char {
-        'n' | 'N' => break Command::New,
-        'q' | 'Q' => break Command::Quit,
-        _ => ()
+      let command = match Key::from_ncurses(key).and_then(|key| self.key_map.action(key)) {
+        Some(Action::PowerUp(power_up)) => self.power_up_command(power_up),
+        Some(Action::Record) => self.record_command(),
+        Some(Action::Play) => self.play_command(),
+        Some(action) => action.command(),
+        None => None
+      };
+      if let Some(command) = command {
+        match confirmation_question(&command, &self.game.borrow(), self.settings) {
+          Some(question) if !self.confirmed(question) => (),
+          _ => break command,
+        }
+      }
+    }
+  }
+}
+
+impl<'a> Controller for NCursesController<'a> {
+  fn view(&self) -> &dyn View { &self.view }
+
+  fn game(&self) -> Ref<'_, Game> { self.game.borrow() }
+
+  fn mut_game(&self) -> RefMut<'_, Game> { self.game.borrow_mut() }
+
+  fn receive_command(&self) -> Command {
+    if let Some(command) = self.played_command() {
+      return command;
+    }
+    let command = self.player_command();
+    self.recorder.record(&command, &self.game.borrow());
+    command
+  }
+
+  // a key pressed during an animation cuts it short, so that the next move is shown at once
+  fn run_game(&self) {
+    loop {
+      self.view.start_update();
+      let command = self.receive_command(); // draws the animation while waiting
+      self.view.finish_update();
+      self.mut_game().execute(command);
+      if let GameState::Quit = self.game().state() {
+        break;
       }
     }
   }
 }
+
+// Like getch() with the given timeout (-1: none), but draws the frames of the views' running animations
+// while waiting
+pub(super) fn getch_animating(views: &[&NCursesView], timeout_millis: i32) -> i32 {
+  let next_frame_delay =
+    || views.iter().filter(|view| view.is_animating()).map(|view| view.frame_delay()).min();
+  while let Some(frame_delay) = next_frame_delay() {
+    nc::timeout(frame_delay.as_millis() as i32);
+    let key = nc::getch();
+    if key != nc::ERR {
+      nc::timeout(timeout_millis);
+      return key;
+    }
+    for view in views.iter() {
+      view.draw_next_frame();
+    }
+  }
+  nc::timeout(timeout_millis);
+  nc::getch()
+}
+
+// the direction of the longer component of a displacement by `dy` lines and `dx` columns. None, if both
+// are shorter than `min_length`.
+fn main_direction(dy: f32, dx: f32, min_length: f32) -> Option<Direction> {
+  if dy.abs() < min_length && dx.abs() < min_length {
+    None
+  } else if dx.abs() > dy.abs() {
+    Some(if dx > 0.0 { Direction::Right } else { Direction::Left })
+  } else {
+    Some(if dy > 0.0 { Direction::Down } else { Direction::Up })
+  }
+}
@@ -0,0 +1,23 @@
+use super::main_direction;
+use crate::game::Direction;
+
+#[test]
+fn drags_move_along_the_longer_component() {
+  assert_eq!(main_direction(1.0, 0.0, 1.0), Some(Direction::Down));
+  assert_eq!(main_direction(-3.0, 2.0, 1.0), Some(Direction::Up));
+  assert_eq!(main_direction(2.0, -3.0, 1.0), Some(Direction::Left));
+  assert_eq!(main_direction(0.0, 1.5, 1.0), Some(Direction::Right));
+}
+
+#[test]
+fn clicks_near_the_center_do_not_move() {
+  assert_eq!(main_direction(0.0, 0.0, 0.5), None);
+  assert_eq!(main_direction(-0.4, 0.4, 0.5), None);
+  assert_eq!(main_direction(0.4, 0.5, 0.5), Some(Direction::Right));
+}
+
+#[test]
+fn ties_move_vertically() {
+  assert_eq!(main_direction(1.0, 1.0, 0.5), Some(Direction::Down));
+  assert_eq!(main_direction(-1.0, -1.0, 0.5), Some(Direction::Up));
+}
@@ -0,0 +1,114 @@
+use ncurses as nc;
+use std::cell::{Ref, RefCell, RefMut};
+
+use super::keys::{Action, Key, KeyMap};
+use super::ncurses::getch_animating;
+use super::Controller;
+use crate::game::{Command, Game, GameState};
+use crate::race::{OpponentStatus, Race};
+use crate::signals;
+use crate::views::{NCursesView, View};
+
+// Plays against a remote opponent on the same seed. Keys are polled with a timeout, so that the
+// opponent's progress is shown even while the local player does not move.
+pub struct RaceController<'a> {
+  game:         &'a RefCell<Game>,
+  view:         NCursesView<'a>,
+  race:         Race,
+  key_map:      KeyMap,
+  shown_status: RefCell<Option<OpponentStatus>>
+}
+
+impl<'a> RaceController<'a> {
+  const POLL_MILLIS: i32 = 200;
+
+  pub fn create(game: &'a RefCell<Game>, view: NCursesView<'a>, race: Race, key_map: KeyMap)
+                -> RaceController<'a> {
+    nc::cbreak();
+    nc::keypad(nc::stdscr(), true);
+    nc::noecho();
+    nc::timeout(Self::POLL_MILLIS);
+    RaceController { game, view, race, key_map, shown_status: RefCell::new(None) }
+  }
+
+  fn show_opponent(&self, opponent: &OpponentStatus) {
+    let game = self.game.borrow();
+    let own_over = matches!(game.state(), GameState::Over);
+    let mut lines = vec![format!("Score:    {}", opponent.score),
+                         format!("Max tile: {}", opponent.max_tile),
+                         format!("Moves:    {}", opponent.moves),
+                         String::new()];
+    if opponent.disconnected {
+      lines.push(String::from("disconnected"));
+    } else if opponent.over {
+      lines.push(String::from("game over"));
+    }
+    if own_over && (opponent.over || opponent.disconnected) {
+      lines.push(String::from(if game.score() > opponent.score {
+                                "YOU WIN"
+                              } else if game.score() < opponent.score {
+                                "YOU LOSE"
+                              } else {
+                                "DRAW"
+                              }));
+    }
+    self.view.set_side_panel("Opponent", &lines);
+  }
+}
+
+impl<'a> Controller for RaceController<'a> {
+  fn view(&self) -> &dyn View { &self.view }
+
+  fn game(&self) -> Ref<'_, Game> { self.game.borrow() }
+
+  fn mut_game(&self) -> RefMut<'_, Game> { self.game.borrow_mut() }
+
+  fn receive_command(&self) -> Command {
+    loop {
+      if signals::interrupted() {
+        break Command::Quit;
+      }
+      let key = getch_animating(&[&self.view], Self::POLL_MILLIS);
+      match key {
+        nc::ERR => {
+          // timeout ⇒ repaint if the opponent has made progress
+          let opponent = self.race.opponent();
+          if self.shown_status.borrow().as_ref() != Some(&opponent) {
+            break Command::Nop;
+          }
+        },
+        nc::KEY_RESIZE => break Command::Nop, // window resize event
+        _ => ()
+      }
+      match Key::from_ncurses(key).and_then(|key| self.key_map.action(key)) {
+        // no 'new' in a race: both players must play the same seed. No power-ups, pausing or macros either.
+        Some(Action::New) | Some(Action::PowerUp(_)) | Some(Action::Help) | None => (),
+        Some(Action::Record) | Some(Action::Play) => (),
+        Some(action) =>
+          if let Some(command) = action.command() {
+            break command;
+          },
+      }
+    }
+  }
+
+  fn run_game(&self) {
+    self.race.send_status(&self.game());
+    loop {
+      let opponent = self.race.opponent();
+      self.show_opponent(&opponent);
+      self.shown_status.replace(Some(opponent));
+      self.view.start_update();
+      let command = self.receive_command();
+      self.view.finish_update();
+      let move_count = self.game().move_count();
+      self.mut_game().execute(command);
+      if self.game().move_count() != move_count {
+        self.race.send_status(&self.game());
+      }
+      if let GameState::Quit = self.game().state() {
+        break;
+      }
+    }
+  }
+}
@@ -0,0 +1,82 @@
+use log::warn;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use crate::game::{Command, Direction, Game};
+use crate::macros::Macros;
+
+// Records the player's moves into a register and plays macros back (see macros.rs) for the interactive
+// controllers. Recorded macros are saved at once.
+pub struct Recorder {
+  macros:    RefCell<Macros>,
+  recording: RefCell<Option<(char, Vec<Direction>)>>,
+  playback:  RefCell<VecDeque<Direction>>
+}
+
+impl Recorder {
+  pub fn new() -> Recorder {
+    let macros = Macros::load().unwrap_or_else(|msg| {
+                                 warn!("{}", msg);
+                                 Macros::default()
+                               });
+    Recorder { macros:    RefCell::new(macros),
+               recording: RefCell::new(None),
+               playback:  RefCell::new(VecDeque::new()) }
+  }
+
+  // e.g. ["a: LLURD"]
+  pub fn list(&self) -> Vec<String> { self.macros.borrow().list() }
+
+  // the register being recorded into
+  pub fn recording(&self) -> Option<char> { self.recording.borrow().as_ref().map(|(register, _)| *register) }
+
+  pub fn start_recording(&self, register: char) { self.recording.replace(Some((register, vec![]))); }
+
+  // Err, if the macro cannot be saved. It can be played anyway. Nothing is stored without moves.
+  pub fn stop_recording(&self) -> Result<(), String> {
+    if let Some((register, moves)) = self.recording.replace(None).filter(|(_, moves)| !moves.is_empty()) {
+      self.macros.borrow_mut().set(register, moves);
+      self.macros.borrow().save()?;
+    }
+    Ok(())
+  }
+
+  // to be called before `command` is executed. Only possible moves are recorded.
+  pub fn record(&self, command: &Command, game: &Game) {
+    if let (Some((_, moves)), Some(direction)) = (self.recording.borrow_mut().as_mut(), command.direction()) {
+      if game.board.possible_directions().contains(&direction) {
+        moves.push(direction);
+      }
+    }
+  }
+
+  // false, if nothing is recorded in `register`
+  pub fn play(&self, register: char) -> bool {
+    match self.macros.borrow().get(register) {
+      Some(moves) if !moves.is_empty() => {
+        self.playback.replace(moves.iter().copied().collect());
+        true
+      },
+      _ => false
+    }
+  }
+
+  pub fn is_playing(&self) -> bool { !self.playback.borrow().is_empty() }
+
+  pub fn stop_playing(&self) { self.playback.borrow_mut().clear(); }
+
+  // the next move of the macro being played. Err and the playback stops, if the move is not possible on
+  // the board of `game`.
+  pub fn next_move(&self, game: &Game) -> Result<Option<Command>, String> {
+    let direction = match self.playback.borrow_mut().pop_front() {
+      Some(direction) => direction,
+      None => return Ok(None)
+    };
+    if game.board.possible_directions().contains(&direction) {
+      Ok(Some(Command::from(direction)))
+    } else {
+      self.stop_playing();
+      Err(format!("Macro stopped: {:?} is not possible", direction))
+    }
+  }
+}
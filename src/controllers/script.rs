@@ -0,0 +1,74 @@
+use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use super::Controller;
+use crate::game::{Command, Game};
+use crate::script;
+use crate::views::{NoView, View};
+
+// Executes a move script (see script.rs) line by line, so that it may come from a pipe. Ends with the
+// script, or at its first invalid line.
+pub struct ScriptController<'a> {
+  game:        &'a RefCell<Game>,
+  view:        Box<dyn View + 'a>,
+  lines:       RefCell<Box<dyn BufRead>>,
+  line_number: Cell<usize>,
+  pending:     RefCell<VecDeque<Command>>, // the rest of the current line
+  executed:    Cell<usize>,
+  error:       RefCell<Option<String>>
+}
+
+impl<'a> ScriptController<'a> {
+  // Each step is shown in `view`, if there is one.
+  pub fn create(game: &'a RefCell<Game>, view: Option<Box<dyn View + 'a>>, lines: Box<dyn BufRead>)
+                -> ScriptController<'a> {
+    ScriptController { game,
+                       view: view.unwrap_or_else(|| Box::new(NoView)),
+                       lines: RefCell::new(lines),
+                       line_number: Cell::new(0),
+                       pending: RefCell::new(VecDeque::new()),
+                       executed: Cell::new(0),
+                       error: RefCell::new(None) }
+  }
+
+  // number of commands executed so far
+  pub fn executed(&self) -> usize { self.executed.get() }
+
+  // the reason for ending early: an invalid line or a read error
+  pub fn error(&self) -> Option<String> { self.error.borrow().clone() }
+
+  fn fail(&self, msg: String) -> Command {
+    self.error.replace(Some(msg));
+    Command::Quit
+  }
+}
+
+impl<'a> Controller for ScriptController<'a> {
+  fn view(&self) -> &dyn View { self.view.as_ref() }
+
+  fn game(&self) -> Ref<'_, Game> { self.game.borrow() }
+
+  fn mut_game(&self) -> RefMut<'_, Game> { self.game.borrow_mut() }
+
+  fn receive_command(&self) -> Command {
+    loop {
+      if let Some(command) = self.pending.borrow_mut().pop_front() {
+        self.executed.set(self.executed.get() + 1);
+        return command;
+      }
+      let mut line = String::new();
+      match self.lines.borrow_mut().read_line(&mut line) {
+        Ok(0) => return Command::Quit, // end of script
+        Ok(_) => {
+          self.line_number.set(self.line_number.get() + 1);
+          match script::parse_line(&line) {
+            Ok(commands) => self.pending.borrow_mut().extend(commands),
+            Err(msg) => return self.fail(format!("line {}: {}", self.line_number.get(), msg))
+          }
+        },
+        Err(e) => return self.fail(format!("Cannot read the script: {}", e))
+      }
+    }
+  }
+}
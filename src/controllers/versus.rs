@@ -0,0 +1,111 @@
+use ncurses as nc;
+use std::cell::RefCell;
+
+use super::keys::{Action, Key, KeyMap};
+use super::ncurses::getch_animating;
+use crate::game::{Command, Game, GameState, TileValue};
+use crate::signals;
+use crate::views::NCursesView;
+
+const PLAYER_ONE: usize = 0;
+const PLAYER_TWO: usize = 1;
+
+// Two players on one keyboard, each with an own game. The first one to reach the target tile wins, or
+// the last one who can still move.
+pub struct VersusController<'a> {
+  games:       [&'a RefCell<Game>; 2],
+  views:       [NCursesView<'a>; 2],
+  target_tile: TileValue,
+  key_map:     KeyMap, // letters for player 1, arrow keys for player 2
+  names:       [String; 2]
+}
+
+impl<'a> VersusController<'a> {
+  pub fn create(games: [&'a RefCell<Game>; 2], views: [NCursesView<'a>; 2], key_map: KeyMap,
+                target_tile: TileValue)
+                -> VersusController<'a> {
+    nc::cbreak();
+    nc::keypad(nc::stdscr(), true);
+    nc::noecho();
+    let key_map = key_map.with_letters_for_moving();
+    let names = [format!("Player 1 ({})", key_map.letters_for_moving()), String::from("Player 2 (arrows)")];
+    VersusController { games, views, target_tile, key_map, names }
+  }
+
+  // Both games are animated at the same time. A key pressed meanwhile cuts the animations short.
+  pub fn run_game(&self) {
+    let mut winner = None;
+    loop {
+      self.show_captions(winner);
+      for view in self.views.iter() {
+        view.start_update();
+      }
+      let command = self.receive_command(); // draws the animations while waiting
+      for view in self.views.iter() {
+        view.finish_update();
+      }
+      match command {
+        (_, Command::Quit) => break,
+        (_, Command::New) => {
+          for game in self.games.iter() {
+            game.borrow_mut().execute(Command::New);
+          }
+          winner = None;
+        },
+        (Some(player), command) =>
+          if winner.is_none() {
+            self.games[player].borrow_mut().execute(command);
+            winner = self.winner(player);
+          },
+        (None, _) => () // repaint only
+      }
+    }
+  }
+
+  // (None, _) for commands concerning both players
+  fn receive_command(&self) -> (Option<usize>, Command) {
+    loop {
+      if signals::interrupted() {
+        break (None, Command::Quit);
+      }
+      let key = getch_animating(&[&self.views[PLAYER_ONE], &self.views[PLAYER_TWO]], -1);
+      if key == nc::KEY_RESIZE {
+        break (None, Command::Nop); // window resize event
+      }
+      let key = match Key::from_ncurses(key) {
+        Some(key) => key,
+        None => continue
+      };
+      match self.key_map.action(key) {
+        Some(Action::New) => break (None, Command::New),
+        Some(Action::Quit) => break (None, Command::Quit),
+        Some(action) =>
+          if let Some(direction) = action.direction() {
+            let player = if let Key::Char(_) = key { PLAYER_ONE } else { PLAYER_TWO };
+            break (Some(player), Command::from(direction));
+          },
+        None => ()
+      }
+    }
+  }
+
+  // checks whether the latest move of `player` decided the match
+  fn winner(&self, player: usize) -> Option<usize> {
+    let game = self.games[player].borrow();
+    match (game.board.max_tile(), game.state()) {
+      (Some((_, max_tile)), _) if max_tile >= self.target_tile => Some(player),
+      (_, GameState::Over) => Some(1 - player),
+      _ => None
+    }
+  }
+
+  fn show_captions(&self, winner: Option<usize>) {
+    for (player, view) in self.views.iter().enumerate() {
+      let caption = match winner {
+        Some(winner) if winner == player => format!("{} WINS", self.names[player]),
+        _ => self.names[player].clone()
+      };
+      view.set_caption(&caption);
+    }
+  }
+}
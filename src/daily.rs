@@ -0,0 +1,60 @@
+// The daily challenge: everybody gets the same tiles on the same day. Results are appended to a
+// history file in the home directory.
+
+#[cfg(test)]
+mod test;
+
+use chrono::{Datelike, Local, NaiveDate};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::game::Game;
+use crate::home_path;
+
+pub const BOARD_SIZE: usize = 4;
+const HISTORY_FILE_NAME: &str = ".game2048_daily";
+
+pub struct DailyResult {
+  date:     NaiveDate,
+  score:    u32,
+  max_tile: u16,
+  moves:    usize
+}
+
+pub fn today() -> NaiveDate { Local::now().date_naive() }
+
+// e.g. 20261019 for 19 October 2026
+pub fn seed(date: NaiveDate) -> u64 {
+  date.year() as u64 * 10000 + date.month() as u64 * 100 + date.day() as u64
+}
+
+impl DailyResult {
+  pub fn of(date: NaiveDate, game: &Game) -> Self {
+    DailyResult { date,
+                  score: game.score(),
+                  max_tile: game.board.max_tile().map_or(0, |(_, value)| value),
+                  moves: game.shift_count() }
+  }
+
+  pub fn summary(&self) -> String {
+    format!("2048 daily {}: score {}, max tile {}, {} moves",
+            self.date, self.score, self.max_tile, self.moves)
+  }
+
+  // appends a line "<date> <score> <max tile> <moves>" to the history file
+  pub fn record(&self) -> io::Result<PathBuf> {
+    let path = home_path(HISTORY_FILE_NAME)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", self)?;
+    Ok(path)
+  }
+}
+
+// a line of the history file
+impl fmt::Display for DailyResult {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {} {} {}", self.date, self.score, self.max_tile, self.moves)
+  }
+}
@@ -0,0 +1,19 @@
+use chrono::NaiveDate;
+
+use super::{seed, DailyResult};
+
+#[test]
+fn seed_is_the_date_as_a_number() {
+  assert_eq!(seed(NaiveDate::from_ymd_opt(2026, 10, 19).unwrap()), 20261019);
+  assert_eq!(seed(NaiveDate::from_ymd_opt(2027, 1, 2).unwrap()), 20270102);
+}
+
+#[test]
+fn result_is_written_as_a_history_line() {
+  let result = DailyResult { date:     NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+                             score:    1234,
+                             max_tile: 128,
+                             moves:    150 };
+  assert_eq!(result.to_string(), "2026-10-19 1234 128 150");
+  assert_eq!(result.summary(), "2048 daily 2026-10-19: score 1234, max tile 128, 150 moves");
+}
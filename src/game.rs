@@ -1,52 +1,309 @@
 mod board;
+#[cfg(test)]
+mod test;
 
-pub use board::{Board, Coord, Move, Randomizer, Square};
+pub use board::{Board, Coord, Direction, Move, Randomizer, Spawn, SpawnSequence, Spawner, Square, Symmetry,
+                TileValue};
 use log::info;
+use rand::XorShiftRng;
+use std::time::{Duration, Instant};
+
+use crate::puzzle::Puzzle;
+
+// Reaching this tile awards the first power-up. Each doubling of it awards the next one (see PowerUp::ALL).
+const FIRST_MILESTONE: u32 = 32;
 
 #[derive(Debug)]
 pub enum Command {
-  Nop, // no operation. Used for repainting
+  Nop,  // no operation. Used for repainting
+  Tick, // time has passed. Sent regularly by controllers while the player does not move
   New,
   Quit,
+  Hint, // asks the adviser, if there is one (see Game::set_adviser)
+  Undo, // takes back the latest change of the board. New tiles are not taken back from the spawner.
   Right,
   Left,
   Up,
-  Down
+  Down,
+  Swap(Coord, Coord), // power-ups
+  Delete(Coord),
+  Shuffle,
+  Pause, // stops the clock of a timed game. Only Resume, Quit and Nop are executed until Resume.
+  Resume
+}
+
+impl From<Direction> for Command {
+  fn from(direction: Direction) -> Self {
+    match direction {
+      Direction::Left => Command::Left,
+      Direction::Right => Command::Right,
+      Direction::Up => Command::Up,
+      Direction::Down => Command::Down
+    }
+  }
+}
+
+impl Command {
+  // None for commands other than moves
+  pub fn direction(&self) -> Option<Direction> {
+    match self {
+      Command::Left => Some(Direction::Left),
+      Command::Right => Some(Direction::Right),
+      Command::Up => Some(Direction::Up),
+      Command::Down => Some(Direction::Down),
+      _ => None
+    }
+  }
+}
+
+// A recommended direction, together with the rating of each direction by the hint strategy (None if
+// impossible)
+pub struct Hint {
+  pub direction: Option<Direction>,
+  pub ratings:   Vec<(Direction, Option<f64>)>
+}
+
+// Limited-use helpers for the player, awarded by reaching milestone tiles
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PowerUp {
+  Swap,    // swaps two tiles
+  Delete,  // removes a tile
+  Shuffle  // moves all tiles to random squares
+}
+
+impl PowerUp {
+  pub const ALL: [PowerUp; 3] = [PowerUp::Swap, PowerUp::Delete, PowerUp::Shuffle];
+
+  pub fn name(self) -> &'static str {
+    match self {
+      PowerUp::Swap => "swap",
+      PowerUp::Delete => "delete",
+      PowerUp::Shuffle => "shuffle"
+    }
+  }
+}
+
+// Optional rules for timed games and challenges. The clock starts with each new game.
+#[derive(Clone, Copy, Default)]
+pub struct Rules {
+  pub spawn_interval: Option<Duration>, // time attack: a new tile appears at least this often
+  pub time_limit:     Option<Duration>, // the game is finished when the clock runs out
+  pub move_limit:     Option<usize>,    // the game is finished after this many shifts
+  pub power_ups:      bool              // reaching milestone tiles awards power-ups
+}
+
+impl Rules {
+  // no limits, with power-ups
+  pub fn classic() -> Rules { Rules { power_ups: true, ..Rules::default() } }
+}
+
+// what is shown of the next new tile, one move in advance
+#[derive(Clone, Copy, PartialEq)]
+pub enum Preview {
+  Value,
+  ValueAndSquare // if the next shift fills the square, the tile appears elsewhere
+}
+
+#[derive(Clone)]
+pub struct NextTile {
+  pub value: TileValue,
+  pub at:    Option<Coord> // only with Preview::ValueAndSquare
+}
+
+// what Command::Undo restores
+struct Snapshot {
+  board:       Board,
+  score:       u32,
+  shift_count: usize,
+  power_ups:   [usize; 3],
+  milestone:   Option<u32>,
+  next_tile:   Option<NextTile>,
+  last_spawn:  Instant
 }
 
 pub enum GameState {
   Running,
-  // Over, // Game Over - cannot continue playing
-  Quit // Game ended by user request
+  Won,      // the goal tile has been reached
+  Over,     // Game Over - cannot continue playing
+  Finished, // the move or time limit has been reached
+  Quit      // Game ended by user request
 }
 
+impl GameState {
+  pub fn name(&self) -> &'static str {
+    match self {
+      GameState::Running => "running",
+      GameState::Won => "won",
+      GameState::Over => "over",
+      GameState::Finished => "finished",
+      GameState::Quit => "quit"
+    }
+  }
+}
+
+// rates the directions on a board for Command::Hint, e.g. with a strategy
+pub type Adviser = Box<dyn FnMut(&Board) -> Hint>;
+
+// see Game::set_on_end
+type OnEnd = Box<dyn FnMut(&Game)>;
+
 pub struct Game {
   pub board:    Board,
-  randomizer:   Randomizer,
+  spawner:      Box<dyn Spawner>,
   state:        GameState,
   latest_moves: Vec<Move>,
-  move_count:   usize
+  move_count:   usize, // counts all changes of the board, including restarts
+  shift_count:  usize, // counts the player's shifts since the last restart
+  score:        u32,
+  hint:         Option<Hint>,
+  adviser:      Option<Adviser>,
+  start_board:  Option<Board>, // None: start with a single new tile
+  start_score:  u32,
+  goal:         Option<TileValue>,
+  preview:      Option<Preview>,
+  next_tile:    Option<NextTile>,
+  power_ups:    [usize; 3],      // indexed by PowerUp
+  milestone:    Option<u32>,     // the next tile that awards a power-up. None: no power-ups in this game
+  rng:          XorShiftRng,     // for shuffling. Seeded by the spawner.
+  rules:        Rules,
+  started:      Instant,
+  last_spawn:   Instant,
+  paused_at:    Option<Instant>,
+  history:      Vec<Snapshot>, // before each change of the board since the last restart
+  on_end:       Option<OnEnd>
 }
 
 impl Game {
-  pub fn new(size_x: usize, size_y: usize) -> Game {
-    let mut new_game = Game { state:        GameState::Running,
-                              board:        Board::new(size_x, size_y),
-                              randomizer:   Randomizer::new(),
-                              latest_moves: Vec::new(),
-                              move_count:   0 };
+  pub fn new(size_x: usize, size_y: usize, spawner: Box<dyn Spawner>) -> Game {
+    let mut new_game = Self::unstarted(size_x, size_y, spawner);
     new_game.execute(Command::New);
     new_game
   }
 
+  // a game without tiles. Command::New starts it.
+  fn unstarted(size_x: usize, size_y: usize, spawner: Box<dyn Spawner>) -> Game {
+    Game { state: GameState::Running,
+           board: Board::new(size_x, size_y),
+           spawner,
+           latest_moves: Vec::new(),
+           move_count: 0,
+           shift_count: 0,
+           score: 0,
+           hint: None,
+           adviser: None,
+           start_board: None,
+           start_score: 0,
+           goal: None,
+           preview: None,
+           next_tile: None,
+           power_ups: [0; 3],
+           milestone: None,
+           rng: rand::weak_rng(),
+           rules: Rules::default(),
+           started: Instant::now(),
+           last_spawn: Instant::now(),
+           paused_at: None,
+           history: vec![],
+           on_end: None }
+  }
+
+  pub fn from_puzzle(puzzle: &Puzzle) -> Game {
+    let mut new_game = Self::unstarted(puzzle.board.size_x(),
+                                       puzzle.board.size_y(),
+                                       Box::new(SpawnSequence::new(puzzle.spawns.clone())));
+    new_game.goal = Some(puzzle.goal);
+    new_game.rules.move_limit = puzzle.move_limit;
+    new_game.start_from(puzzle.board.clone(), 0);
+    new_game
+  }
+
+  // Starts over from `board` instead of a single new tile, e.g. from a loaded or edited position. A new
+  // game starts from there again.
+  pub fn start_from(&mut self, board: Board, score: u32) {
+    self.start_board = Some(board);
+    self.start_score = score;
+    self.execute(Command::New);
+  }
+
+  // replaces the board without a move, e.g. in the board editor
+  pub fn set_board(&mut self, board: Board) {
+    self.latest_moves = board.stays();
+    self.board = board;
+    self.hint = None;
+  }
+
+  // Starts a new game with the given rules.
+  pub fn set_rules(&mut self, rules: Rules) {
+    self.rules = rules;
+    self.execute(Command::New);
+  }
+
+  // Starts a new game with new tiles from `spawner`.
+  pub fn set_spawner(&mut self, spawner: Box<dyn Spawner>) {
+    self.spawner = spawner;
+    self.execute(Command::New);
+  }
+
+  pub fn set_adviser(&mut self, adviser: Adviser) { self.adviser = Some(adviser); }
+
+  // Calls `on_end` each time a game is won, over or finished, e.g. for recording its result at once.
+  pub fn set_on_end(&mut self, on_end: OnEnd) { self.on_end = Some(on_end); }
+
+  // Draws each new tile one move in advance, so that it can be shown to the player.
+  pub fn set_preview(&mut self, preview: Preview) {
+    self.preview = Some(preview);
+    self.draw_next_tile();
+  }
+
   pub fn execute(&mut self, command: Command) {
+    if self.paused_at.is_some() && !matches!(command, Command::Resume | Command::Quit | Command::Nop) {
+      info!("Game command: {:?} (paused)", command);
+      return;
+    }
+    let was_running = matches!(self.state, GameState::Running);
+    // only for commands that may change the board
+    let snapshot = match command {
+      Command::Tick if !self.tick_due() => None,
+      Command::Tick
+      | Command::Left
+      | Command::Right
+      | Command::Up
+      | Command::Down
+      | Command::Swap(..)
+      | Command::Delete(_)
+      | Command::Shuffle => Some(self.snapshot()),
+      _ => None
+    };
     match match command {
             Command::Nop => None, // screen refresh only
-            Command::Left => self.shift_left(),
-            Command::Right => self.shift_right(),
-            Command::Up => self.shift_up(),
-            Command::Down => self.shift_down(),
-            Command::New => Some(vec![self.restart()]),
+            Command::Tick => self.tick(),
+            Command::Left => self.shift(Direction::Left),
+            Command::Right => self.shift(Direction::Right),
+            Command::Up => self.shift(Direction::Up),
+            Command::Down => self.shift(Direction::Down),
+            Command::New => Some(self.restart()),
+            Command::Undo => self.undo(),
+            Command::Swap(a, b) => self.use_power_up(PowerUp::Swap, |board| board.swap(a, b)),
+            Command::Delete(at) => self.use_power_up(PowerUp::Delete, |board| board.remove(at)),
+            Command::Shuffle => {
+              let mut rng = self.rng.clone();
+              let moves = self.use_power_up(PowerUp::Shuffle, |board| Some(board.shuffle(&mut rng)));
+              self.rng = rng;
+              moves
+            },
+            Command::Pause => {
+              self.paused_at = Some(Instant::now());
+              None
+            },
+            Command::Resume => {
+              self.resume();
+              None
+            },
+            Command::Hint => {
+              let board = &self.board;
+              self.hint = self.adviser.as_mut().map(|advise| advise(board));
+              None
+            },
             Command::Quit => {
               self.state = GameState::Quit;
               info!("Game command: {:?}", command);
@@ -55,11 +312,23 @@ impl Game {
           } {
       Some(new_moves) => {
         info!("Game command: {:?}", command);
+        self.history.extend(snapshot);
+        self.score += new_moves.iter().map(Move::points).sum::<u32>();
         self.latest_moves = new_moves;
         self.move_count += 1;
+        self.hint = None;
+        self.award_power_ups();
+        self.update_state();
       },
+      None if self.is_timed() && matches!(command, Command::Tick) => (), // too many to log
       None => info!("Game command: {:?} (no move)", command)
     }
+    if was_running && !matches!(self.state, GameState::Running) {
+      if let Some(mut on_end) = self.on_end.take() {
+        on_end(self);
+        self.on_end = Some(on_end);
+      }
+    }
   }
 
   pub fn state(&self) -> &GameState { &self.state }
@@ -68,42 +337,231 @@ impl Game {
 
   pub fn move_count(&self) -> usize { self.move_count }
 
-  fn restart(&mut self) -> Move { self.board.initialize(&mut self.randomizer) }
+  pub fn shift_count(&self) -> usize { self.shift_count }
 
-  fn shift_left(&mut self) -> Option<Vec<Move>> {
-    match self.board.shift_left() {
-      Some(mut moves) => {
-        moves.push(self.board.new_tile(&mut self.randomizer));
-        Some(moves)
-      },
-      None => None
+  pub fn score(&self) -> u32 { self.score }
+
+  pub fn hint(&self) -> Option<&Hint> { self.hint.as_ref() }
+
+  // how often `power_up` can still be used
+  pub fn power_ups(&self, power_up: PowerUp) -> usize { self.power_ups[power_up as usize] }
+
+  // None, unless in preview mode
+  pub fn next_tile(&self) -> Option<&NextTile> { self.next_tile.as_ref() }
+
+  // the tile to reach, if any
+  pub fn goal(&self) -> Option<TileValue> { self.goal }
+
+  // true, if Command::Tick may change the game
+  pub fn is_timed(&self) -> bool { self.rules.spawn_interval.is_some() || self.rules.time_limit.is_some() }
+
+  // true, if Command::Tick would change the game now
+  pub fn tick_due(&self) -> bool {
+    let since_spawn = self.now().duration_since(self.last_spawn);
+    let spawn_due = self.rules.spawn_interval.is_some_and(|interval| since_spawn >= interval);
+    let time_up = self.time_left() == Some(Duration::from_secs(0));
+    matches!(self.state, GameState::Running) && (spawn_due || time_up)
+  }
+
+  // None, if there is no time limit
+  pub fn time_left(&self) -> Option<Duration> {
+    let elapsed = self.now().duration_since(self.started);
+    self.rules.time_limit.map(|limit| limit.checked_sub(elapsed).unwrap_or_default())
+  }
+
+  pub fn rules(&self) -> Rules { self.rules }
+
+  pub fn is_paused(&self) -> bool { self.paused_at.is_some() }
+
+  // the time on the game's clock, which stands still while the game is paused
+  fn now(&self) -> Instant { self.paused_at.unwrap_or_else(Instant::now) }
+
+  // moves the clock on by the time spent paused
+  fn resume(&mut self) {
+    if let Some(paused_at) = self.paused_at.take() {
+      let paused = paused_at.elapsed();
+      self.started += paused;
+      self.last_spawn += paused;
     }
   }
 
-  fn shift_right(&mut self) -> Option<Vec<Move>> {
-    match self.board.shift_right() {
-      Some(mut moves) => {
-        moves.push(self.board.new_tile(&mut self.randomizer));
-        Some(moves)
-      },
-      None => None
+  // None, if the number of moves is unlimited
+  pub fn moves_left(&self) -> Option<usize> {
+    self.rules.move_limit.map(|limit| limit.saturating_sub(self.shift_count))
+  }
+
+  // true, if no more moves or no more time are left
+  pub fn limit_reached(&self) -> bool {
+    self.moves_left() == Some(0) || self.time_left() == Some(Duration::from_secs(0))
+  }
+
+  // true, if starting a new game or quitting would throw away moves of a game that is not over yet
+  pub fn in_progress(&self) -> bool { self.shift_count > 0 && matches!(self.state, GameState::Running) }
+
+  fn restart(&mut self) -> Vec<Move> {
+    self.state = GameState::Running;
+    self.score = self.start_score;
+    self.shift_count = 0;
+    self.power_ups = [0; 3];
+    // Tiles already on the start board award nothing.
+    let start_max_tile =
+      self.start_board.as_ref().and_then(Board::max_tile).map_or(0, |(_, value)| value as u32);
+    self.milestone = if self.rules.power_ups { Some(FIRST_MILESTONE.max(2 * start_max_tile)) } else { None };
+    self.spawner.restart();
+    self.rng = self.spawner.seed().map_or_else(rand::weak_rng, Randomizer::seeded_rng);
+    self.started = Instant::now();
+    self.last_spawn = self.started;
+    self.paused_at = None;
+    self.history.clear();
+    let tiles = match &self.start_board {
+      None => vec![self.board.initialize(self.spawner.as_mut())],
+      Some(start_board) => {
+        self.board = start_board.clone();
+        let mut tiles = vec![];
+        for x in 0..self.board.size_x() {
+          for y in 0..self.board.size_y() {
+            if let Square::Value(value) = self.board.at_xy(x, y) {
+              tiles.push(Move::Appear { at: self.board.coord(x, y), value });
+            }
+          }
+        }
+        tiles
+      }
+    };
+    self.draw_next_tile();
+    tiles
+  }
+
+  fn draw_next_tile(&mut self) {
+    self.next_tile = match self.preview {
+      None => None,
+      Some(preview) => {
+        // on a full board, the square is drawn as if it were empty. It may be free after the next shift.
+        let (coord, value) = match self.board.num_empty_squares() {
+          0 => self.spawner.spawn(&Board::new(self.board.size_x(), self.board.size_y())),
+          _ => self.spawner.spawn(&self.board)
+        };
+        let at = if preview == Preview::ValueAndSquare { Some(coord) } else { None };
+        Some(NextTile { value, at })
+      }
     }
   }
 
-  fn shift_up(&mut self) -> Option<Vec<Move>> {
-    match self.board.shift_up() {
-      Some(mut moves) => {
-        moves.push(self.board.new_tile(&mut self.randomizer));
-        Some(moves)
-      },
-      None => None
+  fn new_tile(&mut self) -> Move {
+    match self.next_tile.take() {
+      None => self.board.new_tile(self.spawner.as_mut()),
+      Some(next_tile) => {
+        let at = match next_tile.at {
+          Some(at) if self.board.at(at) == Square::Empty => at,
+          _ => self.spawner.spawn(&self.board).0 // the value is kept
+        };
+        self.board.put(at, Square::Value(next_tile.value));
+        self.draw_next_tile();
+        Move::Appear { at, value: next_tile.value }
+      }
+    }
+  }
+
+  pub fn goal_reached(&self) -> bool {
+    match (self.goal, self.board.max_tile()) {
+      (Some(goal), Some((_, max_tile))) => max_tile >= goal,
+      _ => false
     }
   }
 
-  fn shift_down(&mut self) -> Option<Vec<Move>> {
-    match self.board.shift_down() {
+  fn update_state(&mut self) {
+    if self.goal_reached() {
+      info!("Goal reached. Score: {}", self.score);
+      self.state = GameState::Won;
+    } else if self.limit_reached() {
+      info!("Game finished. Score: {}", self.score);
+      self.state = GameState::Finished;
+    } else if !self.board.can_shift() {
+      info!("Game over. Score: {}", self.score);
+      self.state = GameState::Over;
+    }
+  }
+
+  // In time attack, a new tile appears when it is due. The game is over if there is no room for it.
+  fn tick(&mut self) -> Option<Vec<Move>> {
+    if !self.tick_due() {
+      return None;
+    }
+    if self.time_left() == Some(Duration::from_secs(0)) {
+      self.update_state();
+      return None;
+    }
+    self.last_spawn = Instant::now();
+    if self.board.num_empty_squares() == 0 {
+      info!("Board overflow. Score: {}", self.score);
+      self.state = GameState::Over;
+      return None;
+    }
+    let mut moves = self.board.stays();
+    moves.push(self.new_tile());
+    Some(moves)
+  }
+
+  fn snapshot(&self) -> Snapshot {
+    Snapshot { board:       self.board.clone(),
+               score:       self.score,
+               shift_count: self.shift_count,
+               power_ups:   self.power_ups,
+               milestone:   self.milestone,
+               next_tile:   self.next_tile.clone(),
+               last_spawn:  self.last_spawn }
+  }
+
+  // None, if there is nothing to undo
+  fn undo(&mut self) -> Option<Vec<Move>> {
+    let snapshot = self.history.pop()?;
+    self.board = snapshot.board;
+    self.score = snapshot.score;
+    self.shift_count = snapshot.shift_count;
+    self.power_ups = snapshot.power_ups;
+    self.milestone = snapshot.milestone;
+    self.next_tile = snapshot.next_tile;
+    self.last_spawn = snapshot.last_spawn;
+    self.state = GameState::Running; // updated after the command
+    Some(self.board.stays())
+  }
+
+  // None, if the power-up is used up or has no effect
+  fn use_power_up<F>(&mut self, power_up: PowerUp, change: F) -> Option<Vec<Move>>
+    where F: FnOnce(&mut Board) -> Option<Vec<Move>> {
+    if self.power_ups(power_up) == 0 || !matches!(self.state, GameState::Running | GameState::Over) {
+      return None;
+    }
+    let moves = change(&mut self.board)?;
+    self.power_ups[power_up as usize] -= 1;
+    self.state = GameState::Running; // a power-up may save a game that was over
+    Some(moves)
+  }
+
+  fn award_power_ups(&mut self) {
+    let max_tile = match self.board.max_tile() {
+      Some((_, value)) => value as u32,
+      None => return
+    };
+    while let Some(milestone) = self.milestone.filter(|&milestone| milestone <= max_tile) {
+      let index = (milestone / FIRST_MILESTONE).trailing_zeros() as usize % PowerUp::ALL.len();
+      info!("Reached {}: one more {} power-up", milestone, PowerUp::ALL[index].name());
+      self.power_ups[index] += 1;
+      self.milestone = Some(milestone * 2);
+    }
+  }
+
+  fn shift(&mut self, direction: Direction) -> Option<Vec<Move>> {
+    if let GameState::Won | GameState::Over | GameState::Finished = self.state {
+      return None;
+    }
+    if self.time_left() == Some(Duration::from_secs(0)) {
+      return None; // the next tick ends the game
+    }
+    match self.board.shift(direction) {
       Some(mut moves) => {
-        moves.push(self.board.new_tile(&mut self.randomizer));
+        moves.push(self.new_tile());
+        self.shift_count += 1;
         Some(moves)
       },
       None => None
@@ -1,17 +1,24 @@
 mod coord;
 mod cursor;
+mod direction;
+mod features;
 mod merger;
 mod r#move;
+mod rearrange;
+mod spawner;
+mod symmetry;
 #[cfg(test)]
 mod test;
 
 pub use coord::Coord;
+pub use direction::Direction;
 pub use r#move::Move;
+pub use spawner::{Randomizer, Spawn, SpawnSequence, Spawner};
+pub use symmetry::Symmetry;
 
 use coord::Vector;
 use cursor::DualCursor;
 use merger::Merger;
-use rand::{distributions::IndependentSample, Rng};
 use std::cell::UnsafeCell;
 use std::sync::mpsc;
 use Square::*;
@@ -20,34 +27,25 @@ pub type TileValue = u16;
 
 #[derive(Copy, Clone)] // needed for easy Board initialization
 #[derive(Debug)] // only needed for console view. TODO: remove or define in views/console.rs, if possible
-#[derive(PartialEq)] // needed for tests
+#[derive(PartialEq, Eq, Hash)] // needed for tests and for comparing boards
+#[derive(PartialOrd, Ord)] // needed for finding the canonical form of a board
 pub enum Square {
   Empty,
   Value(TileValue),
 }
 
-type XYGrid = Vec<Vec<Square>>;
-
-pub struct Randomizer {
-  rand_range_10: rand::distributions::Range<u8>,
-  rng: rand::ThreadRng,
-}
-
-impl Randomizer {
-  pub fn new() -> Self {
-    Randomizer { rand_range_10: rand::distributions::Range::new(0, 10), rng: rand::thread_rng() }
-  }
-
-  fn ten_percent_chance(&mut self) -> bool {
-    self.rand_range_10.ind_sample(&mut self.rng) == 0
-  }
-
-  fn gen_int_less_than(&mut self, upper_bound: usize) -> usize {
-    self.rng.gen_range(0, upper_bound)
+impl Square {
+  pub fn value(self) -> Option<TileValue> {
+    match self {
+      Empty => None,
+      Value(value) => Some(value),
+    }
   }
 }
 
-#[derive(Clone, Debug)]
+type XYGrid = Vec<Vec<Square>>;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Board {
   max_x: usize, // used as array index -> must be typed 'usize'
   max_y: usize, // used as array index -> must be typed 'usize'
@@ -59,25 +57,34 @@ impl Board {
     Board { max_x: size_x - 1, max_y: size_y - 1, grid: Self::empty_grid(size_x, size_y) }
   }
 
+  // e.g. Board::with_rows(&[[2, 0], [4, 8]]), where 0 means an empty square
+  #[cfg(test)]
+  pub fn with_rows<R: AsRef<[TileValue]>>(rows: &[R]) -> Self {
+    let mut board = Board::new(rows[0].as_ref().len(), rows.len());
+    for (y, row) in rows.iter().enumerate() {
+      for (x, &value) in row.as_ref().iter().enumerate() {
+        board.put(board.coord(x, y), if value == 0 { Empty } else { Value(value) });
+      }
+    }
+    board
+  }
+
   pub fn coord(&self, x: usize, y: usize) -> Coord {
     Coord::new(x, y, self.max_x, self.max_y)
   }
 
-  pub fn initialize(&mut self, randomizer: &mut Randomizer) -> Move {
+  pub fn initialize(&mut self, spawner: &mut dyn Spawner) -> Move {
     self.grid = Self::empty_grid(self.size_x(), self.size_y());
-    self.new_tile(randomizer)
+    self.new_tile(spawner)
   }
 
-  pub fn new_tile(&mut self, randomizer: &mut Randomizer) -> Move {
-    let num_free_tiles = self.num_free_tiles();
-    if num_free_tiles == 0 {
+  pub fn new_tile(&mut self, spawner: &mut dyn Spawner) -> Move {
+    if self.num_empty_squares() == 0 {
       panic!("tried to place a new tile on a full board")
     };
-    let n = randomizer.gen_int_less_than(num_free_tiles);
-    let rnd_free_coord = self.find_free_tile(n);
-    let new_value = if randomizer.ten_percent_chance() { 4 } else { 2 };
-    self.put(rnd_free_coord, Value(new_value));
-    Move::Appear { at: rnd_free_coord, value: new_value }
+    let (coord, new_value) = spawner.spawn(self);
+    self.put(coord, Value(new_value));
+    Move::Appear { at: coord, value: new_value }
   }
 
   pub fn size_x(&self) -> usize {
@@ -100,6 +107,28 @@ impl Board {
     self.grid[coord.x][coord.y] = square;
   }
 
+  pub fn shift(&mut self, direction: Direction) -> Option<Vec<Move>> {
+    match direction {
+      Direction::Left => self.shift_left(),
+      Direction::Right => self.shift_right(),
+      Direction::Up => self.shift_up(),
+      Direction::Down => self.shift_down(),
+    }
+  }
+
+  // Same result as shift(), but without a thread per row. Used by the solver and the strategies, which
+  // shift a lot of small boards.
+  pub fn shift_single_threaded(&mut self, direction: Direction) -> Option<Vec<Move>> {
+    let vector = match direction {
+      Direction::Left => Vector::new(1, 0),
+      Direction::Right => Vector::new(-1, 0),
+      Direction::Up => Vector::new(0, 1),
+      Direction::Down => Vector::new(0, -1),
+    };
+    let cursors = self.slice_in_direction(vector);
+    Self::real_moves(cursors.into_iter().flat_map(|cursor| Merger::new(cursor).merge()).collect())
+  }
+
   pub fn shift_left(&mut self) -> Option<Vec<Move>> {
     self.contract_multi(Vector::new(1, 0))
   }
@@ -116,20 +145,19 @@ impl Board {
     self.contract_multi(Vector::new(0, 1))
   }
 
-  fn empty_grid(size_x: usize, size_y: usize) -> XYGrid {
-    vec![vec![Square::Empty; size_y]; size_x]
+  // true, if there is at least one direction in which tiles can be shifted
+  pub fn can_shift(&self) -> bool {
+    self.num_empty_squares() > 0 || self.num_merges() > 0
   }
 
-  fn num_free_tiles(&self) -> usize {
-    let mut n = 0;
-    for column in &self.grid {
-      for square in column {
-        if let Empty = square {
-          n += 1;
-        }
-      }
-    }
-    n
+  // the directions in which tiles can be shifted
+  pub fn possible_directions(&self) -> Vec<Direction> {
+    let can_shift_in = |direction: &Direction| self.clone().shift_single_threaded(*direction).is_some();
+    Direction::ALL.iter().copied().filter(can_shift_in).collect()
+  }
+
+  fn empty_grid(size_x: usize, size_y: usize) -> XYGrid {
+    vec![vec![Square::Empty; size_y]; size_x]
   }
 
   fn find_free_tile(&self, n: usize) -> Coord {
@@ -144,10 +172,10 @@ impl Board {
         }
       }
     }
-    panic!(); // n > self.num_free_tiles()
+    panic!(); // n > self.num_empty_squares()
   }
 
-  fn slice_in_direction(&mut self, direction: Vector) -> Vec<DualCursor> {
+  fn slice_in_direction(&mut self, direction: Vector) -> Vec<DualCursor<'_>> {
     let start_coords: Vec<Coord> = match direction {
       Vector { dx: 1, dy: 0 } => (0..=self.max_y).map(|y| self.coord(0, y)).collect(),
       Vector { dx: -1, dy: 0 } => (0..=self.max_y).map(|y| self.coord(self.max_x, y)).collect(),
@@ -179,7 +207,10 @@ impl Board {
       }
     })
     .unwrap();
-    let moves: Vec<Move> = receiver.into_iter().collect();
+    Self::real_moves(receiver.into_iter().collect())
+  }
+
+  fn real_moves(moves: Vec<Move>) -> Option<Vec<Move>> {
     // Return Some(moves) only if there are any _real_ moves. Otherwise return None:
     for mv in moves.iter() {
       match mv {
@@ -13,7 +13,7 @@ impl std::cmp::PartialEq for Coord {
 impl Coord {
   pub fn new(x: usize, y: usize, max_x: usize, max_y: usize) -> Self { Coord { x, y, max_x, max_y } }
 
-  pub fn add(&self, vector: Vector) -> Result<Self, ()> {
+  pub(crate) fn add(&self, vector: Vector) -> Result<Self, ()> {
     // TODO: enable use of '+' operator (not trivial with Rhs being of different
     // type)
     let new_x = self.x as isize + vector.dx;
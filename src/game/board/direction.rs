@@ -0,0 +1,11 @@
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+  Left,
+  Right,
+  Up,
+  Down
+}
+
+impl Direction {
+  pub const ALL: [Direction; 4] = [Direction::Left, Direction::Right, Direction::Up, Direction::Down];
+}
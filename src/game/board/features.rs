@@ -0,0 +1,134 @@
+// Metrics for rating a board. They are used by the strategies, but may also be useful for analysing
+// games. Where tile values are compared, their rank (2 => 1, 4 => 2, 8 => 3, ...) is used.
+
+#[cfg(test)]
+mod test;
+
+use super::{Board, Coord, Square::*, TileValue};
+
+impl Board {
+  pub fn num_empty_squares(&self) -> usize {
+    let mut n = 0;
+    for column in &self.grid {
+      for square in column {
+        if let Empty = square {
+          n += 1;
+        }
+      }
+    }
+    n
+  }
+
+  // Number of merges that would happen if all rows were shifted horizontally, plus those if all
+  // columns were shifted vertically.
+  pub fn num_merges(&self) -> usize {
+    let rows = (0..self.size_y()).map(|y| Self::num_merges_in_line(&self.row(y)));
+    let columns = (0..self.size_x()).map(|x| Self::num_merges_in_line(&self.column(x)));
+    rows.chain(columns).sum()
+  }
+
+  // 0, if the ranks of the tiles in row `y` are monotonic (empty squares are skipped). Otherwise the
+  // smaller of the sum of all rises and the sum of all falls between neighbouring tiles.
+  pub fn row_monotonicity(&self, y: usize) -> u32 { Self::monotonicity_of_line(&self.row(y)) }
+
+  // like row_monotonicity(), for column `x`
+  pub fn column_monotonicity(&self, x: usize) -> u32 { Self::monotonicity_of_line(&self.column(x)) }
+
+  // sum of the monotonicity of all rows and columns. 0 is best.
+  pub fn monotonicity(&self) -> u32 {
+    let rows: u32 = (0..self.size_y()).map(|y| self.row_monotonicity(y)).sum();
+    let columns: u32 = (0..self.size_x()).map(|x| self.column_monotonicity(x)).sum();
+    rows + columns
+  }
+
+  // Sum of rank differences between horizontally or vertically adjacent tiles. Empty squares are
+  // ignored. 0 is best.
+  pub fn smoothness(&self) -> u32 {
+    let mut sum = 0;
+    for x in 0..self.size_x() {
+      for y in 0..self.size_y() {
+        if let Value(value) = self.grid[x][y] {
+          if x < self.max_x {
+            if let Value(right) = self.grid[x + 1][y] {
+              sum += Self::rank_difference(value, right);
+            }
+          }
+          if y < self.max_y {
+            if let Value(below) = self.grid[x][y + 1] {
+              sum += Self::rank_difference(value, below);
+            }
+          }
+        }
+      }
+    }
+    sum
+  }
+
+  // The highest tile and its position. If it occurs more than once, the first one in column order.
+  pub fn max_tile(&self) -> Option<(Coord, TileValue)> {
+    let mut max: Option<(Coord, TileValue)> = None;
+    for x in 0..self.size_x() {
+      for y in 0..self.size_y() {
+        if let Value(value) = self.grid[x][y] {
+          match max {
+            Some((_, max_value)) if max_value >= value => (),
+            _ => max = Some((self.coord(x, y), value))
+          }
+        }
+      }
+    }
+    max
+  }
+
+  pub fn tile_sum(&self) -> u32 {
+    let mut sum = 0;
+    for column in &self.grid {
+      for square in column {
+        if let Value(value) = square {
+          sum += *value as u32;
+        }
+      }
+    }
+    sum
+  }
+
+  // tile values of row `y` from left to right, without empty squares
+  fn row(&self, y: usize) -> Vec<TileValue> {
+    (0..self.size_x()).filter_map(|x| self.grid[x][y].value()).collect()
+  }
+
+  // tile values of column `x` from top to bottom, without empty squares
+  fn column(&self, x: usize) -> Vec<TileValue> {
+    self.grid[x].iter().filter_map(|square| square.value()).collect()
+  }
+
+  fn num_merges_in_line(values: &[TileValue]) -> usize {
+    let mut n = 0;
+    let mut i = 1;
+    while i < values.len() {
+      if values[i - 1] == values[i] {
+        n += 1;
+        i += 2; // a merged tile cannot merge again in the same move
+      } else {
+        i += 1;
+      }
+    }
+    n
+  }
+
+  fn monotonicity_of_line(values: &[TileValue]) -> u32 {
+    let (mut rises, mut falls) = (0, 0);
+    for pair in values.windows(2) {
+      if pair[1] > pair[0] {
+        rises += Self::rank_difference(pair[0], pair[1]);
+      } else {
+        falls += Self::rank_difference(pair[0], pair[1]);
+      }
+    }
+    rises.min(falls)
+  }
+
+  fn rank_difference(a: TileValue, b: TileValue) -> u32 {
+    (a.trailing_zeros() as i32 - b.trailing_zeros() as i32).unsigned_abs()
+  }
+}
@@ -0,0 +1,70 @@
+use super::super::Board;
+
+#[test]
+fn empty_board() {
+  let board = Board::with_rows(&[[0, 0, 0], [0, 0, 0], [0, 0, 0]]);
+  assert_eq!(board.num_empty_squares(), 9);
+  assert_eq!(board.num_merges(), 0);
+  assert_eq!(board.monotonicity(), 0);
+  assert_eq!(board.smoothness(), 0);
+  assert!(board.max_tile().is_none());
+  assert_eq!(board.tile_sum(), 0);
+}
+
+#[test]
+fn num_empty_squares() {
+  assert_eq!(Board::with_rows(&[[2, 0, 4], [0, 0, 0], [8, 2, 0]]).num_empty_squares(), 5);
+}
+
+#[test]
+fn num_merges_across_empty_squares() {
+  assert_eq!(Board::with_rows(&[[2, 0, 2], [0, 0, 0], [0, 0, 0]]).num_merges(), 1);
+}
+
+#[test]
+fn num_merges_merged_tile_does_not_merge_again() {
+  assert_eq!(Board::with_rows(&[[2, 2, 2], [0, 0, 0], [0, 0, 0]]).num_merges(), 1);
+  assert_eq!(Board::with_rows(&[[2, 2, 2, 2]]).num_merges(), 2);
+}
+
+#[test]
+fn num_merges_horizontal_and_vertical() {
+  assert_eq!(Board::with_rows(&[[2, 2, 0], [2, 0, 0], [0, 0, 0]]).num_merges(), 2);
+}
+
+#[test]
+fn monotonic_row() {
+  let board = Board::with_rows(&[[2, 4, 0, 16]]);
+  assert_eq!(board.row_monotonicity(0), 0);
+}
+
+#[test]
+fn non_monotonic_row() {
+  // rises: 2→8 (2 ranks), falls: 8→4 (1 rank)
+  let board = Board::with_rows(&[[2, 8, 4, 4]]);
+  assert_eq!(board.row_monotonicity(0), 1);
+}
+
+#[test]
+fn column_monotonicity() {
+  let board = Board::with_rows(&[[16, 2], [2, 2], [16, 2]]);
+  assert_eq!(board.column_monotonicity(0), 3);
+  assert_eq!(board.column_monotonicity(1), 0);
+  assert_eq!(board.monotonicity(), 3); // all rows only fall or stay equal
+}
+
+#[test]
+fn smoothness_ignores_empty_squares() {
+  let board = Board::with_rows(&[[2, 8, 0], [2, 0, 4], [0, 0, 0]]);
+  assert_eq!(board.smoothness(), 2);
+}
+
+#[test]
+fn max_tile_first_in_column_order() {
+  let board = Board::with_rows(&[[2, 8, 0], [8, 0, 4], [0, 0, 0]]);
+  let (coord, value) = board.max_tile().unwrap();
+  assert_eq!((coord.x, coord.y, value), (0, 1, 8));
+}
+
+#[test]
+fn tile_sum() { assert_eq!(Board::with_rows(&[[2, 8, 0], [8, 0, 4], [0, 0, 0]]).tile_sum(), 22); }
@@ -5,5 +5,16 @@ pub enum Move {
   Appear { at: Coord, value: u16 },
   Shift { from: Coord, to: Coord, value: u16 },
   Merge { from: Coord, to: Coord, start_value: u16, end_value: u16 },
-  Stay { at: Coord, value: u16 }
+  Stay { at: Coord, value: u16 },
+  Vanish { at: Coord, value: u16 } // a tile removed by a power-up
+}
+
+impl Move {
+  // like in the original game, each merge scores the value of the resulting tile
+  pub fn points(&self) -> u32 {
+    match self {
+      Move::Merge { end_value, .. } => *end_value as u32,
+      _ => 0
+    }
+  }
 }
@@ -0,0 +1,76 @@
+// Changes of the board other than shifts, as used by power-ups. Like shifts, they return a move for
+// every tile, so that views can animate them.
+
+use rand::Rng;
+
+use super::{Board, Coord, Move, Square::*};
+
+impl Board {
+  // None, if there is nothing to swap (both squares are empty, or they are the same square)
+  pub fn swap(&mut self, a: Coord, b: Coord) -> Option<Vec<Move>> {
+    if a == b || (self.at(a) == Empty && self.at(b) == Empty) {
+      return None;
+    }
+    let (square_a, square_b) = (self.at(a), self.at(b));
+    let mut moves = self.stays_except(&[a, b]);
+    if let Value(value) = square_a {
+      moves.push(Move::Shift { from: a, to: b, value });
+    }
+    if let Value(value) = square_b {
+      moves.push(Move::Shift { from: b, to: a, value });
+    }
+    self.put(a, square_b);
+    self.put(b, square_a);
+    Some(moves)
+  }
+
+  // None, if the square is empty, or if it holds the only tile: an empty board could not be shifted
+  pub fn remove(&mut self, at: Coord) -> Option<Vec<Move>> {
+    let value = self.at(at).value()?;
+    if self.num_empty_squares() + 1 == self.size_x() * self.size_y() {
+      return None;
+    }
+    let mut moves = self.stays_except(&[at]);
+    moves.push(Move::Vanish { at, value });
+    self.put(at, Empty);
+    Some(moves)
+  }
+
+  // moves all tiles to random squares
+  pub fn shuffle<R: Rng>(&mut self, rng: &mut R) -> Vec<Move> {
+    let mut squares = vec![];
+    for x in 0..self.size_x() {
+      for y in 0..self.size_y() {
+        squares.push(self.coord(x, y));
+      }
+    }
+    let mut targets = squares.clone();
+    rng.shuffle(&mut targets);
+    let old_board = self.clone();
+    let mut moves = vec![];
+    for (&from, &to) in squares.iter().zip(targets.iter()) {
+      let square = old_board.at(from);
+      self.put(to, square);
+      if let Value(value) = square {
+        moves.push(Move::Shift { from, to, value });
+      }
+    }
+    moves
+  }
+
+  // a move for every tile, without changing anything
+  pub fn stays(&self) -> Vec<Move> { self.stays_except(&[]) }
+
+  fn stays_except(&self, coords: &[Coord]) -> Vec<Move> {
+    let mut moves = vec![];
+    for x in 0..self.size_x() {
+      for y in 0..self.size_y() {
+        let at = self.coord(x, y);
+        if let (Value(value), false) = (self.at(at), coords.contains(&at)) {
+          moves.push(Move::Stay { at, value });
+        }
+      }
+    }
+    moves
+  }
+}
@@ -0,0 +1,114 @@
+use rand::{distributions::IndependentSample, Rng, SeedableRng, XorShiftRng};
+
+use super::{Board, Coord, Square, TileValue};
+
+// Decides where a new tile appears, and with which value.
+pub trait Spawner {
+  // `board` has at least one empty square
+  fn spawn(&mut self, board: &Board) -> (Coord, TileValue);
+
+  // called when a new game starts
+  fn restart(&mut self) {}
+
+  // seeds the other random choices of a game, e.g. shuffling, so that they repeat along with the new tiles.
+  // None: they are different each time.
+  fn seed(&self) -> Option<u64> { Some(0) }
+}
+
+// The spawner of the original game: a 2 (or a 4 with 10% probability) on a random empty square.
+pub struct Randomizer {
+  rand_range_10: rand::distributions::Range<u8>,
+  rng: XorShiftRng,
+  seed: Option<u64>,
+}
+
+impl Randomizer {
+  pub fn new() -> Self {
+    Randomizer { rand_range_10: rand::distributions::Range::new(0, 10), rng: rand::weak_rng(), seed: None }
+  }
+
+  // Two randomizers created from the same seed produce the same sequence of new tiles. This sequence
+  // starts again with each new game.
+  pub fn with_seed(seed: u64) -> Self {
+    Randomizer {
+      rand_range_10: rand::distributions::Range::new(0, 10),
+      rng: Self::seeded_rng(seed),
+      seed: Some(seed),
+    }
+  }
+
+  pub fn seeded_rng(seed: u64) -> XorShiftRng {
+    // XorShiftRng must not be seeded with all zeros => pad with arbitrary constants
+    XorShiftRng::from_seed([seed as u32, (seed >> 32) as u32, 0x2048_2048, 0x9e37_79b9])
+  }
+
+  fn ten_percent_chance(&mut self) -> bool {
+    self.rand_range_10.ind_sample(&mut self.rng) == 0
+  }
+
+  fn gen_int_less_than(&mut self, upper_bound: usize) -> usize {
+    self.rng.gen_range(0, upper_bound)
+  }
+}
+
+impl Spawner for Randomizer {
+  fn spawn(&mut self, board: &Board) -> (Coord, TileValue) {
+    let n = self.gen_int_less_than(board.num_empty_squares());
+    let rnd_free_coord = board.find_free_tile(n);
+    let new_value = if self.ten_percent_chance() { 4 } else { 2 };
+    (rnd_free_coord, new_value)
+  }
+
+  fn restart(&mut self) {
+    if let Some(seed) = self.seed {
+      self.rng = Self::seeded_rng(seed);
+    }
+  }
+
+  fn seed(&self) -> Option<u64> { self.seed }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spawn {
+  pub value: TileValue,
+  pub at: Option<(usize, usize)>, // None: the first empty square
+}
+
+impl Spawn {
+  // where the tile appears on `board`, which has at least one empty square
+  pub fn place(&self, board: &Board) -> (Coord, TileValue) {
+    match self.at {
+      Some((x, y)) if x < board.size_x() && y < board.size_y() && board.at_xy(x, y) == Square::Empty =>
+        (board.coord(x, y), self.value),
+      _ => (board.find_free_tile(0), self.value),
+    }
+  }
+}
+
+// A fixed list of new tiles, e.g. for puzzles. If the given square is not empty, the tile appears on
+// the first empty square (column by column). After the last tile, the list starts over.
+pub struct SpawnSequence {
+  spawns: Vec<Spawn>,
+  next: usize,
+}
+
+impl SpawnSequence {
+  pub fn new(spawns: Vec<Spawn>) -> Self {
+    SpawnSequence { spawns, next: 0 }
+  }
+}
+
+impl Spawner for SpawnSequence {
+  fn spawn(&mut self, board: &Board) -> (Coord, TileValue) {
+    let spawn = match self.spawns.get(self.next) {
+      Some(spawn) => spawn.clone(),
+      None => Spawn { value: 2, at: None }, // empty list
+    };
+    self.next = (self.next + 1) % self.spawns.len().max(1);
+    spawn.place(board)
+  }
+
+  fn restart(&mut self) {
+    self.next = 0;
+  }
+}
@@ -0,0 +1,116 @@
+// The rotations and reflections of a board. Tiles keep their values, only their positions change.
+// Playing the transformed board in the mapped direction gives the transformed result of playing the
+// original board in the original direction. Hence, symmetric positions are equivalent.
+
+#[cfg(test)]
+mod test;
+
+use super::{Board, Direction};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+  Identity,
+  RotateRight, // 90° clockwise
+  Rotate180,
+  RotateLeft, // 90° counterclockwise
+  FlipHorizontal, // left ⇔ right
+  FlipVertical, // top ⇔ bottom
+  Transpose, // top left ⇔ bottom right diagonal
+  AntiTranspose // top right ⇔ bottom left diagonal
+}
+
+use Symmetry::*;
+
+impl Symmetry {
+  pub const ALL: [Symmetry; 8] =
+    [Identity, RotateRight, Rotate180, RotateLeft, FlipHorizontal, FlipVertical, Transpose, AntiTranspose];
+
+  // symmetries which keep width and height of the board unchanged
+  pub const PRESERVING_SHAPE: [Symmetry; 4] = [Identity, Rotate180, FlipHorizontal, FlipVertical];
+
+  // all 8 symmetries for square boards, the 4 which preserve the shape for others
+  pub fn for_board(board: &Board) -> &'static [Symmetry] {
+    if board.size_x() == board.size_y() {
+      &Self::ALL
+    } else {
+      &Self::PRESERVING_SHAPE
+    }
+  }
+
+  pub fn inverse(self) -> Symmetry {
+    match self {
+      RotateRight => RotateLeft,
+      RotateLeft => RotateRight,
+      other => other,
+    }
+  }
+
+  // true, if width and height are exchanged
+  pub fn swaps_axes(self) -> bool {
+    match self {
+      RotateRight | RotateLeft | Transpose | AntiTranspose => true,
+      Identity | Rotate180 | FlipHorizontal | FlipVertical => false,
+    }
+  }
+
+  // where the square at (x, y) of a board with the given size ends up
+  pub fn map_xy(self, x: usize, y: usize, size_x: usize, size_y: usize) -> (usize, usize) {
+    let (max_x, max_y) = (size_x - 1, size_y - 1);
+    match self {
+      Identity => (x, y),
+      RotateRight => (max_y - y, x),
+      Rotate180 => (max_x - x, max_y - y),
+      RotateLeft => (y, max_x - x),
+      FlipHorizontal => (max_x - x, y),
+      FlipVertical => (x, max_y - y),
+      Transpose => (y, x),
+      AntiTranspose => (max_y - y, max_x - x),
+    }
+  }
+
+  pub fn map_direction(self, direction: Direction) -> Direction {
+    use Direction::*;
+    // images of Left, Right, Up, and Down:
+    let (left, right, up, down) = match self {
+      Identity => (Left, Right, Up, Down),
+      RotateRight => (Up, Down, Right, Left),
+      Rotate180 => (Right, Left, Down, Up),
+      RotateLeft => (Down, Up, Left, Right),
+      FlipHorizontal => (Right, Left, Up, Down),
+      FlipVertical => (Left, Right, Down, Up),
+      Transpose => (Up, Down, Left, Right),
+      AntiTranspose => (Down, Up, Right, Left),
+    };
+    match direction {
+      Left => left,
+      Right => right,
+      Up => up,
+      Down => down,
+    }
+  }
+}
+
+impl Board {
+  pub fn transformed(&self, symmetry: Symmetry) -> Board {
+    let (size_x, size_y) = (self.size_x(), self.size_y());
+    let mut result =
+      if symmetry.swaps_axes() { Board::new(size_y, size_x) } else { Board::new(size_x, size_y) };
+    for x in 0..size_x {
+      for y in 0..size_y {
+        let (new_x, new_y) = symmetry.map_xy(x, y, size_x, size_y);
+        result.grid[new_x][new_y] = self.grid[x][y];
+      }
+    }
+    result
+  }
+
+  // The smallest of all symmetric variants of this board, and the symmetry which transforms this
+  // board into it. Equivalent positions have equal canonical forms.
+  pub fn canonical(&self) -> (Board, Symmetry) {
+    Symmetry::for_board(self)
+      .iter()
+      .map(|&symmetry| (self.transformed(symmetry), symmetry))
+      .min_by(|(a, _), (b, _)| a.cmp(b))
+      .unwrap() // there is always the identity
+  }
+}
@@ -0,0 +1,65 @@
+use super::super::{Board, Direction};
+use super::Symmetry;
+
+#[test]
+fn rotate_right() {
+  let board = Board::with_rows(&[[2, 4, 8], [0, 0, 16]]);
+  assert_eq!(board.transformed(Symmetry::RotateRight), Board::with_rows(&[[0, 2], [0, 4], [16, 8]]));
+}
+
+#[test]
+fn flip_horizontal() {
+  let board = Board::with_rows(&[[2, 4, 8], [0, 0, 16]]);
+  assert_eq!(board.transformed(Symmetry::FlipHorizontal), Board::with_rows(&[[8, 4, 2], [16, 0, 0]]));
+}
+
+#[test]
+fn anti_transpose() {
+  let board = Board::with_rows(&[[2, 4], [8, 0]]);
+  assert_eq!(board.transformed(Symmetry::AntiTranspose), Board::with_rows(&[[0, 4], [8, 2]]));
+}
+
+#[test]
+fn inverse_restores_board() {
+  let board = Board::with_rows(&[[2, 4, 8], [0, 32, 16], [0, 2, 0]]);
+  for &symmetry in Symmetry::ALL.iter() {
+    assert_eq!(board.transformed(symmetry).transformed(symmetry.inverse()), board, "{:?}", symmetry);
+  }
+}
+
+#[test]
+fn shifting_commutes_with_transformation() {
+  let board = Board::with_rows(&[[2, 2, 8, 0], [0, 4, 4, 16], [2, 0, 2, 0], [0, 0, 8, 8]]);
+  for &symmetry in Symmetry::ALL.iter() {
+    for &direction in Direction::ALL.iter() {
+      let mut shifted_then_transformed = board.clone();
+      shifted_then_transformed.shift(direction);
+      let shifted_then_transformed = shifted_then_transformed.transformed(symmetry);
+      let mut transformed_then_shifted = board.transformed(symmetry);
+      transformed_then_shifted.shift(symmetry.map_direction(direction));
+      assert_eq!(transformed_then_shifted, shifted_then_transformed, "{:?} {:?}", symmetry, direction);
+    }
+  }
+}
+
+#[test]
+fn rectangular_board_has_4_symmetries() {
+  assert_eq!(Symmetry::for_board(&Board::new(3, 4)).len(), 4);
+  assert_eq!(Symmetry::for_board(&Board::new(4, 4)).len(), 8);
+}
+
+#[test]
+fn equivalent_boards_have_equal_canonical_form() {
+  let board = Board::with_rows(&[[2, 4, 0], [0, 0, 0], [0, 8, 0]]);
+  for &symmetry in Symmetry::ALL.iter() {
+    let (canonical, _) = board.transformed(symmetry).canonical();
+    assert_eq!(canonical, board.canonical().0, "{:?}", symmetry);
+  }
+}
+
+#[test]
+fn canonical_symmetry_transforms_into_canonical_form() {
+  let board = Board::with_rows(&[[2, 4, 0], [0, 0, 0], [0, 8, 0]]);
+  let (canonical, symmetry) = board.canonical();
+  assert_eq!(board.transformed(symmetry), canonical);
+}
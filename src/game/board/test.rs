@@ -109,3 +109,18 @@ fn board_with_column(column: [u16; 4]) -> Board {
   }
   board
 }
+
+#[test]
+fn same_seed_same_tiles() {
+  let mut board_a = Board::new(4, 4);
+  let mut board_b = Board::new(4, 4);
+  let mut randomizer_a = Randomizer::with_seed(42);
+  let mut randomizer_b = Randomizer::with_seed(42);
+  board_a.initialize(&mut randomizer_a);
+  board_b.initialize(&mut randomizer_b);
+  for _ in 0..8 {
+    board_a.new_tile(&mut randomizer_a);
+    board_b.new_tile(&mut randomizer_b);
+  }
+  assert_eq!(format!("{:?}", board_a), format!("{:?}", board_b));
+}
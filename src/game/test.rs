@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use super::{Board, Command, Direction, Game, GameState, PowerUp, Preview, Randomizer, Rules, Spawn,
+            SpawnSequence, Square};
+
+#[test]
+fn previewed_tile_appears_on_next_shift() {
+  let spawns = vec![Spawn { value: 2, at: Some((0, 0)) }, Spawn { value: 4, at: Some((2, 2)) }];
+  let mut game = Game::new(3, 3, Box::new(SpawnSequence::new(spawns)));
+  game.set_preview(Preview::ValueAndSquare);
+  let next_tile = game.next_tile().unwrap();
+  assert_eq!((next_tile.value, next_tile.at.map(|at| (at.x, at.y))), (4, Some((2, 2))));
+  game.execute(Command::Right);
+  assert_eq!(game.board.at_xy(2, 2), Square::Value(4));
+  assert_eq!(game.next_tile().unwrap().value, 2);
+}
+
+#[test]
+fn milestone_awards_power_up() {
+  let mut game = Game::new(3, 3, Box::new(SpawnSequence::new(vec![])));
+  game.set_rules(Rules::classic());
+  game.board = Board::new(3, 3);
+  game.board.put(game.board.coord(0, 0), Square::Value(16));
+  game.board.put(game.board.coord(1, 0), Square::Value(16));
+  game.execute(Command::Left);
+  assert_eq!(game.power_ups(PowerUp::Swap), 1);
+  let (a, b) = (game.board.coord(0, 0), game.board.coord(0, 1)); // 32 and the new tile
+  game.execute(Command::Swap(a, b));
+  assert_eq!(game.board.at(b), Square::Value(32));
+  assert_eq!(game.power_ups(PowerUp::Swap), 0);
+  let move_count = game.move_count();
+  game.execute(Command::Swap(a, b)); // used up
+  assert_eq!(game.move_count(), move_count);
+}
+
+#[test]
+fn shuffle_repeats_with_the_seed() {
+  let shuffled_board = || {
+    let mut game = Game::new(4, 4, Box::new(Randomizer::with_seed(7)));
+    game.set_rules(Rules::classic());
+    game.power_ups[PowerUp::Shuffle as usize] = 1;
+    game.execute(Command::Shuffle);
+    game.board.clone()
+  };
+  assert_eq!(shuffled_board(), shuffled_board());
+}
+
+#[test]
+fn only_tile_cannot_be_deleted() {
+  let mut game = Game::new(3, 3, Box::new(SpawnSequence::new(vec![])));
+  game.set_rules(Rules::classic());
+  game.power_ups[PowerUp::Delete as usize] = 1;
+  let at = game.board.max_tile().unwrap().0;
+  game.execute(Command::Delete(at));
+  assert_eq!(game.board.num_empty_squares(), 8);
+  assert_eq!(game.power_ups(PowerUp::Delete), 1);
+}
+
+#[test]
+fn challenges_award_no_power_ups() {
+  let mut game = Game::new(3, 3, Box::new(SpawnSequence::new(vec![])));
+  game.set_rules(Rules { move_limit: Some(10), ..Rules::default() });
+  game.board.put(game.board.coord(0, 1), Square::Value(64));
+  game.board.put(game.board.coord(1, 1), Square::Value(64));
+  game.execute(Command::Left);
+  assert!(PowerUp::ALL.iter().all(|&power_up| game.power_ups(power_up) == 0));
+}
+
+#[test]
+fn time_attack_spawns_on_tick() {
+  let mut game = Game::new(3, 3, Box::new(SpawnSequence::new(vec![])));
+  game.set_rules(Rules { spawn_interval: Some(Duration::from_secs(0)), ..Rules::default() });
+  game.execute(Command::Tick);
+  assert_eq!(game.board.num_empty_squares(), 7);
+}
+
+#[test]
+fn game_is_finished_when_time_is_up() {
+  let mut game = Game::new(3, 3, Box::new(SpawnSequence::new(vec![])));
+  game.set_rules(Rules { time_limit: Some(Duration::from_millis(1)), ..Rules::default() });
+  std::thread::sleep(Duration::from_millis(2));
+  game.execute(Command::Tick);
+  assert!(matches!(game.state(), GameState::Finished));
+}
+
+#[test]
+fn paused_game_stops_the_clock() {
+  let mut game = Game::new(3, 3, Box::new(SpawnSequence::new(vec![])));
+  game.set_rules(Rules { time_limit: Some(Duration::from_secs(1)), ..Rules::default() });
+  game.execute(Command::Pause);
+  std::thread::sleep(Duration::from_secs(2));
+  game.execute(Command::Tick);
+  assert!(matches!(game.state(), GameState::Running));
+  game.execute(Command::Resume);
+  assert!(game.time_left().unwrap() > Duration::from_millis(0));
+}
+
+#[test]
+fn undo_restores_board_and_score() {
+  let mut game = Game::new(3, 3, Box::new(SpawnSequence::new(vec![])));
+  game.board = Board::new(3, 3);
+  game.board.put(game.board.coord(0, 0), Square::Value(2));
+  game.board.put(game.board.coord(1, 0), Square::Value(2));
+  let board = game.board.clone();
+  game.execute(Command::Left);
+  assert_eq!(game.score(), 4);
+  game.execute(Command::Undo);
+  assert_eq!((game.board.clone(), game.score(), game.shift_count()), (board, 0, 0));
+  let move_count = game.move_count();
+  game.execute(Command::Undo); // nothing left to undo
+  assert_eq!(game.move_count(), move_count);
+}
+
+#[test]
+fn undo_restores_the_previewed_tile() {
+  let spawns =
+    vec![Spawn { value: 2, at: Some((0, 0)) }, Spawn { value: 4, at: None }, Spawn { value: 2, at: None }];
+  let mut game = Game::new(3, 3, Box::new(SpawnSequence::new(spawns)));
+  game.set_preview(Preview::Value);
+  game.execute(Command::Right);
+  assert_eq!(game.next_tile().unwrap().value, 2);
+  game.execute(Command::Undo);
+  assert_eq!(game.next_tile().unwrap().value, 4);
+}
+
+#[test]
+fn hint_is_shown_until_the_next_move() {
+  let mut game = Game::new(3, 3, Box::new(SpawnSequence::new(vec![Spawn { value: 2, at: Some((0, 0)) }])));
+  game.execute(Command::Hint);
+  assert!(game.hint().is_none()); // no adviser
+  game.set_adviser(crate::strategies::adviser());
+  game.execute(Command::Hint);
+  let hint = game.hint().unwrap();
+  assert!(matches!(hint.direction, Some(Direction::Right) | Some(Direction::Down)));
+  assert!(hint.ratings.iter().any(|&(direction, rating)| direction == Direction::Left && rating.is_none()));
+  game.execute(Command::Right);
+  assert!(game.hint().is_none());
+}
@@ -1,31 +1,371 @@
 use std::cell::RefCell;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::rc::Rc;
 
-use controllers::{ConsoleController, Controller, NCursesController};
-use game::Game;
-use views::{ConsoleView, NCursesView};
+use challenge::ChallengeResult;
+use controllers::{
+  ApiController, BoardEditor, ConsoleController, Controller, EditorExit, EngineController, NCursesController,
+  RaceController, ScriptController, VersusController,
+};
+use daily::DailyResult;
+use game::{Game, Randomizer, Spawner};
+use puzzle::Puzzle;
+use race::{Race, RaceSettings};
+use savegame::SaveGame;
+use solver::{Outcome, Solver, Spawns};
+use tournament::Tournament;
+use views::{Area, ConsoleView, EngineView, NCursesView, View};
 
+mod challenge;
 mod controllers;
+mod daily;
 mod game;
+mod macros;
+mod puzzle;
+mod race;
+mod savegame;
+mod script;
+mod signals;
+mod solver;
+mod strategies;
+mod tournament;
 mod views;
 
+pub use controllers::{KeyMap, Settings};
+pub use game::{Board, Coord, Direction, Preview, Rules, Square, Symmetry, TileValue};
+pub use strategies::NAMES as STRATEGY_NAMES;
+
+const AUTOSAVE_FILE_NAME: &str = ".game2048_autosave";
+
 pub enum ViewType {
   Console,
   NCurses,
 }
 
-pub fn play(view_type: ViewType, size_x: usize, size_y: usize) {
-  let game = RefCell::new(Game::new(size_x, size_y));
+// how new tiles are placed
+pub enum SpawnType {
+  Random,
+  Evil, // an adversary places the worst possible tile
+}
+
+impl SpawnType {
+  // `seed` is only used for random tiles. None means a different game every time.
+  fn spawner(&self, seed: Option<u64>) -> Box<dyn Spawner> {
+    match (self, seed) {
+      (SpawnType::Random, None) => Box::new(Randomizer::new()),
+      (SpawnType::Random, Some(seed)) => Box::new(Randomizer::with_seed(seed)),
+      (SpawnType::Evil, _) => Box::new(strategies::Evil::new()),
+    }
+  }
+}
+
+// With a move or time limit, the game is a challenge: its final result is recorded in a history file and
+// ranked against earlier results of the same challenge. Power-ups are only awarded without limits, whatever
+// `rules.power_ups` says.
+#[allow(clippy::too_many_arguments)]
+pub fn play(
+  view_type: ViewType, key_map: &KeyMap, settings: Settings, spawn_type: SpawnType, preview: Option<Preview>,
+  rules: Rules, size_x: usize, size_y: usize,
+) -> Result<(), String> {
+  let evil = matches!(spawn_type, SpawnType::Evil);
+  let challenge = challenge::name(size_x, size_y, &rules, evil, preview.is_some());
+  let mode = match (rules.spawn_interval, &challenge) {
+    (Some(_), _) => "time attack",
+    (None, Some(_)) => "challenge",
+    (None, None) => "classic",
+  };
+  let mode = if evil { format!("evil {}", mode) } else { String::from(mode) };
+  let game = RefCell::new(Game::new(size_x, size_y, spawn_type.spawner(None)));
+  game.borrow_mut().set_rules(Rules { power_ups: challenge.is_none(), ..rules });
+  if let Some(preview) = preview {
+    game.borrow_mut().set_preview(preview);
+  }
+  // each finished game is recorded at once, in case the player starts a new one. Summaries are printed when
+  // the view is closed.
+  let summaries = Rc::new(RefCell::new(vec![]));
+  if let Some(challenge) = challenge.clone() {
+    let summaries = Rc::clone(&summaries);
+    game.borrow_mut().set_on_end(Box::new(move |game| {
+                       let result = ChallengeResult::of(challenge.clone(), game);
+                       summaries.borrow_mut().push(match result.record() {
+                                               Ok(rank) => result.summary(rank),
+                                               Err(e) => format!("Cannot record the result: {}", e),
+                                             });
+                     }));
+  }
+  run_controller(view_type, key_map, settings, mode, &game);
+  for summary in summaries.borrow().iter() {
+    println!("{}", summary);
+  }
+  if challenge.is_some() && summaries.borrow().is_empty() {
+    println!("Challenge not finished.");
+  }
+  Ok(())
+}
+
+// Today's challenge on a board of fixed size. A new game starts with the same tiles again. The result of
+// each game that ends, and of a game in progress when the player quits, is recorded in the history file and
+// printed as a line to share. Games without a move and interrupted games are not recorded.
+pub fn play_daily(view_type: ViewType, key_map: &KeyMap, settings: Settings) -> Result<(), String> {
+  let today = daily::today();
+  let spawner = SpawnType::Random.spawner(Some(daily::seed(today)));
+  let game = RefCell::new(Game::new(daily::BOARD_SIZE, daily::BOARD_SIZE, spawner));
+  let summaries = Rc::new(RefCell::new(vec![]));
+  let record = |result: DailyResult| match result.record() {
+    Ok(_) => result.summary(),
+    Err(e) => format!("Cannot record the result: {}", e),
+  };
+  {
+    let summaries = Rc::clone(&summaries);
+    let on_end = move |game: &Game| summaries.borrow_mut().push(record(DailyResult::of(today, game)));
+    game.borrow_mut().set_on_end(Box::new(on_end));
+  }
+  run_controller(view_type, key_map, settings, String::from("daily"), &game);
+  let game = game.borrow(); // quit or interrupted. Games that ended have been recorded.
+  if game.shift_count() > 0 && game.board.can_shift() && !signals::interrupted() {
+    summaries.borrow_mut().push(record(DailyResult::of(today, &game)));
+  }
+  for summary in summaries.borrow().iter() {
+    println!("{}", summary);
+  }
+  if summaries.borrow().is_empty() {
+    println!("Daily game not recorded.");
+  }
+  Ok(())
+}
 
+// Starts from the board in the puzzle file at `path`.
+pub fn play_puzzle(
+  view_type: ViewType, key_map: &KeyMap, settings: Settings, path: &str,
+) -> Result<(), String> {
+  let puzzle = Puzzle::load(path)?;
+  let game = RefCell::new(Game::from_puzzle(&puzzle));
+  run_controller(view_type, key_map, settings, String::from("puzzle"), &game);
+  let game = game.borrow();
+  if game.goal_reached() {
+    println!("Puzzle solved in {} moves.", game.shift_count());
+  } else {
+    println!("Puzzle not solved.");
+  }
+  Ok(())
+}
+
+// Continues the game saved in the file at `path`.
+pub fn play_saved(
+  view_type: ViewType, key_map: &KeyMap, settings: Settings, path: &str,
+) -> Result<(), String> {
+  let save_game = SaveGame::load(path)?;
+  let (size_x, size_y) = (save_game.board.size_x(), save_game.board.size_y());
+  let game = RefCell::new(Game::new(size_x, size_y, SpawnType::Random.spawner(None)));
+  game.borrow_mut().set_rules(Rules::classic());
+  game.borrow_mut().start_from(save_game.board, save_game.score);
+  run_controller(view_type, key_map, settings, String::from("classic"), &game);
+  Ok(())
+}
+
+// Lets the player set up a board (ncurses only), then play from there.
+pub fn edit_board(key_map: &KeyMap, settings: Settings, size_x: usize, size_y: usize) {
+  signals::catch();
+  let game = RefCell::new(Game::new(size_x, size_y, SpawnType::Random.spawner(None)));
+  game.borrow_mut().set_rules(Rules::classic());
+  game.borrow_mut().set_board(Board::new(size_x, size_y));
+  game.borrow_mut().set_adviser(strategies::adviser());
+  let view = NCursesView::new(&game);
+  let exit = BoardEditor::create(&game, &view).run();
+  if let EditorExit::Play = exit {
+    NCursesController::create(&game, view, key_map.clone(), settings, String::from("classic")).run_game();
+  }
+}
+
+// Prints a shortest solution of the puzzle file at `path`. With SpawnType::Evil the solution has to work
+// for any new tiles, not just for the ones in the file.
+pub fn solve_puzzle(path: &str, spawn_type: SpawnType, node_limit: Option<usize>) -> Result<(), String> {
+  let puzzle = Puzzle::load(path)?;
+  let spawns = match spawn_type {
+    SpawnType::Random => Spawns::Sequence,
+    SpawnType::Evil => Spawns::WorstCase,
+  };
+  let mut solver = Solver::new(&puzzle, spawns, node_limit.unwrap_or(solver::DEFAULT_NODE_LIMIT));
+  match solver.solve() {
+    Outcome::Solved(line) => {
+      println!("Solved in {} moves ({} positions searched):", line.len(), solver.nodes());
+      for (index, step) in line.iter().enumerate() {
+        let (x, y, value) = step.new_tile;
+        println!("{:3}. {:5} then {}@{},{}", index + 1, format!("{:?}", step.direction), value, x, y);
+      }
+      Ok(())
+    },
+    Outcome::Unsolvable => Err(format!("{} cannot be solved ({} positions searched)", path, solver.nodes())),
+    Outcome::NodeLimitReached => Err(format!("No solution found within {} positions", solver.nodes())),
+  }
+}
+
+// Executes the move script at `path` ("-" for stdin) and prints a summary. Each step is shown in a view of
+// `view_type`, if given. With a `seed`, the new tiles are the same in each run.
+pub fn run_script(
+  path: &str, view_type: Option<ViewType>, spawn_type: SpawnType, seed: Option<u64>, size_x: usize,
+  size_y: usize,
+) -> Result<(), String> {
+  let lines: Box<dyn BufRead> = match path {
+    "-" => Box::new(BufReader::new(io::stdin())),
+    path => Box::new(BufReader::new(File::open(path).map_err(|e| format!("Cannot read {}: {}", path, e))?)),
+  };
+  let game = RefCell::new(Game::new(size_x, size_y, spawn_type.spawner(seed)));
+  game.borrow_mut().set_rules(Rules::classic());
+  game.borrow_mut().set_adviser(strategies::adviser());
+  let (executed, error) = {
+    let view: Option<Box<dyn View>> = match view_type {
+      None => None,
+      Some(ViewType::Console) => Some(Box::new(ConsoleView::new(&game))),
+      Some(ViewType::NCurses) => Some(Box::new(NCursesView::new(&game))),
+    };
+    let controller = ScriptController::create(&game, view, lines);
+    controller.run_game();
+    (controller.executed(), controller.error()) // the view is closed here, before printing
+  };
+  let game = game.borrow();
+  println!("{} commands executed: {} moves, score {}, max tile {}{}",
+           executed,
+           game.shift_count(),
+           game.score(),
+           game.board.max_tile().map_or(0, |(_, value)| value),
+           if game.board.can_shift() { "" } else { ", game over" });
+  error.map_or(Ok(()), Err)
+}
+
+// Lets another program play through a line-based protocol on stdin and stdout (see controllers/engine.rs).
+pub fn run_engine(spawn_type: SpawnType, size_x: usize, size_y: usize) {
+  let game = RefCell::new(Game::new(size_x, size_y, spawn_type.spawner(None)));
+  game.borrow_mut().set_rules(Rules::classic());
+  EngineController::create(&game, EngineView::new(&game)).run_game();
+}
+
+// Serves the game through an HTTP API on localhost:`port` (see controllers/api.rs) until a client quits it.
+// The game is shown in a view of `view_type`, if given.
+pub fn serve_api(
+  port: u16, view_type: Option<ViewType>, spawn_type: SpawnType, size_x: usize, size_y: usize,
+) -> Result<(), String> {
+  let listener =
+    TcpListener::bind(("127.0.0.1", port)).map_err(|e| format!("Cannot listen on port {}: {}", port, e))?;
+  println!("Serving the game on http://localhost:{}/state …", port);
+  let game = RefCell::new(Game::new(size_x, size_y, spawn_type.spawner(None)));
+  game.borrow_mut().set_rules(Rules::classic());
+  let view: Option<Box<dyn View>> = match view_type {
+    None => None,
+    Some(ViewType::Console) => Some(Box::new(ConsoleView::new(&game))),
+    Some(ViewType::NCurses) => Some(Box::new(NCursesView::new(&game))),
+  };
+  ApiController::create(&game, view, listener).run_game();
+  Ok(())
+}
+
+// An interrupted game (Ctrl-C, SIGTERM) is saved in the home directory for continuing it with --load.
+fn run_controller(
+  view_type: ViewType, key_map: &KeyMap, settings: Settings, mode: String, game: &RefCell<Game>,
+) {
+  signals::catch();
+  game.borrow_mut().set_adviser(strategies::adviser());
   let controller: Box<dyn Controller> = match view_type {
     ViewType::Console => {
-      let view = ConsoleView::new(&game);
-      Box::new(ConsoleController::create(&game, view))
+      signals::suspend_in_cooked_mode();
+      let view = ConsoleView::new(game);
+      Box::new(ConsoleController::create(game, view, key_map.clone(), settings, mode))
     },
     ViewType::NCurses => {
-      let view = NCursesView::new(&game);
-      Box::new(NCursesController::create(&game, view))
+      let view = NCursesView::new(game);
+      Box::new(NCursesController::create(game, view, key_map.clone(), settings, mode))
     },
   };
 
   controller.run_game();
+  drop(controller); // closes the view
+  if signals::interrupted() {
+    signals::restore_terminal(); // the console may still be reading a key in raw mode
+    autosave(&game.borrow());
+  }
+}
+
+fn autosave(game: &Game) {
+  if game.move_count() == 0 || !game.board.can_shift() {
+    return;
+  }
+  let save_game = SaveGame { board: game.board.clone(), score: game.score() };
+  let path = match home_path(AUTOSAVE_FILE_NAME) {
+    Ok(path) => path.to_string_lossy().into_owned(),
+    Err(e) => return println!("Game interrupted and not saved: {}", e),
+  };
+  match save_game.save(&path) {
+    Ok(()) => println!("Game interrupted. Continue it with --load {}", path),
+    Err(msg) => println!("Game interrupted and not saved: {}", msg),
+  }
+}
+
+// the file `file_name` in the home directory
+fn home_path(file_name: &str) -> io::Result<PathBuf> {
+  match env::var_os("HOME") {
+    Some(home) => Ok(PathBuf::from(home).join(file_name)),
+    None => Err(io::Error::new(io::ErrorKind::NotFound, "HOME is not set"))
+  }
+}
+
+// Two players on a split screen (ncurses only). Both games get the same random numbers for new tiles.
+// Player 1 moves with the letters of `key_map` (W, A, S and D, if it has none), player 2 with the arrows.
+pub fn play_versus(
+  key_map: &KeyMap, spawn_type: SpawnType, size_x: usize, size_y: usize, target_tile: TileValue,
+) {
+  let seed = Some(rand::random());
+  let game_one = RefCell::new(Game::new(size_x, size_y, spawn_type.spawner(seed)));
+  let game_two = RefCell::new(Game::new(size_x, size_y, spawn_type.spawner(seed)));
+  signals::catch();
+  let views =
+    [NCursesView::in_area(&game_one, Area::LeftHalf), NCursesView::in_area(&game_two, Area::RightHalf)];
+  VersusController::create([&game_one, &game_two], views, key_map.clone(), target_tile).run_game();
+}
+
+// Waits for an opponent to join on `port`, then both play the same game (ncurses only).
+pub fn host_race(key_map: &KeyMap, port: u16, size_x: usize, size_y: usize) -> Result<(), String> {
+  let sizes = RaceSettings::SIZES;
+  if !sizes.contains(&size_x) || !sizes.contains(&size_y) {
+    let (min, max) = (sizes.start(), sizes.end());
+    return Err(format!("Races are played on boards of {} to {} squares per side", min, max));
+  }
+  println!("Waiting for an opponent on port {} …", port);
+  let settings = RaceSettings { seed: rand::random(), size_x, size_y };
+  let race = Race::host(port, &settings).map_err(|e| format!("Cannot host a race: {}", e))?;
+  play_race(key_map, race, settings);
+  Ok(())
+}
+
+// Joins a race hosted at `address` (e.g. "localhost:2048").
+pub fn join_race(key_map: &KeyMap, address: &str) -> Result<(), String> {
+  let (race, settings) = Race::join(address).map_err(|e| format!("Cannot join race at {}: {}", address, e))?;
+  play_race(key_map, race, settings);
+  Ok(())
+}
+
+fn play_race(key_map: &KeyMap, race: Race, settings: RaceSettings) {
+  let spawner = SpawnType::Random.spawner(Some(settings.seed));
+  let game = RefCell::new(Game::new(settings.size_x, settings.size_y, spawner));
+  game.borrow_mut().set_adviser(strategies::adviser());
+  signals::catch();
+  let view = NCursesView::new(&game);
+  RaceController::create(&game, view, race, key_map.clone()).run_game();
+}
+
+// Prints a comparison table to stdout and, if `csv_path` is given, the result of each game as CSV.
+pub fn tournament(
+  strategy_names: &[&str], spawn_type: SpawnType, seeds: u64, size_x: usize, size_y: usize,
+  csv_path: Option<&str>,
+) -> Result<(), String> {
+  let tournament = Tournament::new(strategy_names, spawn_type, 1..seeds + 1, size_x, size_y)?;
+  let results = tournament.run();
+  tournament.write_table(&results, &mut io::stdout()).map_err(|e| e.to_string())?;
+  if let Some(path) = csv_path {
+    tournament.write_csv(&results, path).map_err(|e| format!("Cannot write {}: {}", path, e))?;
+  }
+  Ok(())
 }
@@ -0,0 +1,95 @@
+// Macros are sequences of moves, recorded in-game under a register (a letter) and played back with a key.
+// They are kept in ~/.game2048_macros, one per line, in the direction letters of move scripts (see
+// script.rs):
+//
+//   # comment
+//   a LLURD
+//   o UU LL
+
+#[cfg(test)]
+mod test;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+
+use crate::game::{Command, Direction};
+use crate::home_path;
+use crate::script;
+
+const FILE_NAME: &str = ".game2048_macros";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Macros {
+  registers: BTreeMap<char, Vec<Direction>>
+}
+
+impl Macros {
+  pub fn is_register(c: char) -> bool { c.is_ascii_lowercase() }
+
+  // the macros in the home directory. None recorded yet, if the file does not exist.
+  pub fn load() -> Result<Macros, String> {
+    let path = home_path(FILE_NAME).map_err(|e| e.to_string())?;
+    if !path.exists() {
+      return Ok(Macros::default());
+    }
+    let text = fs::read_to_string(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+  }
+
+  pub fn save(&self) -> Result<(), String> {
+    let path = home_path(FILE_NAME).map_err(|e| e.to_string())?;
+    fs::write(&path, self.to_string()).map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+  }
+
+  pub fn parse(text: &str) -> Result<Macros, String> {
+    let mut macros = Macros::default();
+    for (line_index, line) in text.lines().enumerate() {
+      let error = |msg: String| format!("line {}: {}", line_index + 1, msg);
+      let line = line.trim();
+      let mut chars = line.chars();
+      match (chars.next(), chars.next()) {
+        (None, _) | (Some('#'), _) => (),
+        (Some(register), None) if Self::is_register(register) => macros.set(register, vec![]), // no moves
+        (Some(register), Some(' ')) if Self::is_register(register) => {
+          let commands = script::parse_line(chars.as_str()).map_err(error)?;
+          let moves = commands.iter().map(Command::direction).collect::<Option<Vec<_>>>();
+          macros.set(register, moves.ok_or_else(|| error(String::from("not a move")))?);
+        },
+        _ => return Err(error(format!("no register in \"{}\"", line)))
+      }
+    }
+    Ok(macros)
+  }
+
+  pub fn get(&self, register: char) -> Option<&[Direction]> {
+    self.registers.get(&register).map(Vec::as_slice)
+  }
+
+  pub fn set(&mut self, register: char, moves: Vec<Direction>) { self.registers.insert(register, moves); }
+
+  // e.g. ["a: LLURD", "o: UULL"]
+  pub fn list(&self) -> Vec<String> {
+    self.registers.iter().map(|(register, moves)| format!("{}: {}", register, letters(moves))).collect()
+  }
+}
+
+impl fmt::Display for Macros {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (register, moves) in self.registers.iter() {
+      writeln!(f, "{} {}", register, letters(moves))?;
+    }
+    Ok(())
+  }
+}
+
+fn letters(moves: &[Direction]) -> String {
+  moves.iter()
+       .map(|direction| match direction {
+         Direction::Left => 'L',
+         Direction::Right => 'R',
+         Direction::Up => 'U',
+         Direction::Down => 'D'
+       })
+       .collect()
+}
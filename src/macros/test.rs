@@ -0,0 +1,15 @@
+use super::Macros;
+use crate::game::Direction;
+
+#[test]
+fn macros_are_saved_as_direction_letters() {
+  let macros = Macros::parse("# openings\na LLU\no u d\n").unwrap();
+  assert_eq!(macros.get('a'), Some(&[Direction::Left, Direction::Left, Direction::Up][..]));
+  assert_eq!(macros.list(), vec!["a: LLU", "o: UD"]);
+  assert_eq!(Macros::parse(&macros.to_string()).unwrap(), macros);
+  let mut empty = Macros::default();
+  empty.set('e', vec![]);
+  assert_eq!(Macros::parse(&empty.to_string()).unwrap(), empty);
+  assert_eq!(Macros::parse("a hint").unwrap_err(), "line 1: not a move");
+  assert!(Macros::parse("LLU").is_err());
+}
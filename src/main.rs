@@ -1,7 +1,10 @@
-use clap::{App, Arg}; // argument parser ⇒ https://docs.rs/clap/
+use clap::{App, Arg, ArgMatches}; // argument parser ⇒ https://docs.rs/clap/
                       // use stderrlog; // tracing ⇒ https://docs.rs/log + https://docs.rs/stderrlog
 
-use game2048::ViewType;
+use std::str::FromStr;
+use std::time::Duration;
+
+use game2048::{KeyMap, Preview, Rules, SpawnType, ViewType};
 
 const AUTHOR: &str = "quadruple-output";
 const VERSION: &str = "0.1.1";
@@ -11,6 +14,31 @@ const ARG_VIEW_TYPE: &str = "view_type";
 const ARG_LIST_VIEW_TYPES: &str = "list_types";
 const ARG_BOARD_WIDTH: &str = "width";
 const ARG_BOARD_HEIGHT: &str = "height";
+const ARG_EVIL: &str = "evil";
+const ARG_PREVIEW: &str = "preview";
+const ARG_TIME_ATTACK: &str = "time_attack";
+const ARG_TIME: &str = "time";
+const ARG_MOVES: &str = "moves";
+const ARG_DAILY: &str = "daily";
+const ARG_PUZZLE: &str = "puzzle";
+const ARG_LOAD: &str = "load";
+const ARG_EDIT: &str = "edit";
+const ARG_KEYS: &str = "keys";
+const ARG_NO_CONFIRM: &str = "no_confirm";
+const ARG_SCRIPT: &str = "script";
+const ARG_SHOW: &str = "show";
+const ARG_SEED: &str = "seed";
+const ARG_ENGINE: &str = "engine";
+const ARG_API: &str = "api";
+const ARG_SOLVE: &str = "solve";
+const ARG_NODE_LIMIT: &str = "node_limit";
+const ARG_VERSUS: &str = "versus";
+const ARG_TARGET: &str = "target";
+const ARG_HOST: &str = "host";
+const ARG_JOIN: &str = "join";
+const ARG_TOURNAMENT: &str = "tournament";
+const ARG_SEEDS: &str = "seeds";
+const ARG_CSV: &str = "csv";
 
 fn main() {
   let args = App::new("2048 (Example in Rust)")
@@ -23,7 +51,11 @@ fn main() {
         .value_name("DISPLAY_TYPE")
         .help("Sets the display type. See -l."),
     )
-    .arg(Arg::with_name(ARG_LIST_VIEW_TYPES).short("l").help("Lists available display types and exits"))
+    .arg(
+      Arg::with_name(ARG_LIST_VIEW_TYPES)
+        .short("l")
+        .help("Lists available display types and strategies and exits"),
+    )
     .arg(
       Arg::with_name(ARG_VERBOSITY)
         .short("v")
@@ -44,11 +76,179 @@ fn main() {
         .default_value("3")
         .help("Set the height of the Board"),
     )
+    .arg(
+      Arg::with_name(ARG_EVIL)
+        .short("e")
+        .long("evil")
+        .help("New tiles are not random, but placed by an adversary where they hurt most"),
+    )
+    .arg(
+      Arg::with_name(ARG_PREVIEW)
+        .long("preview")
+        .value_name("WHAT")
+        .possible_values(&["value", "square"])
+        .help("Shows the value (and the square) of the next new tile one move in advance"),
+    )
+    .arg(
+      Arg::with_name(ARG_TIME_ATTACK)
+        .long("time-attack")
+        .value_name("SECONDS")
+        .help("A new tile appears every SECONDS, even if you do not move. Ends when the board overflows."),
+    )
+    .arg(
+      Arg::with_name(ARG_TIME)
+        .long("time")
+        .value_name("SECONDS")
+        .help("Ends the game after SECONDS (default for --time-attack: 120)"),
+    )
+    .arg(
+      Arg::with_name(ARG_MOVES)
+        .long("moves")
+        .value_name("N")
+        .help("Ends the game after N moves"),
+    )
+    .arg(
+      Arg::with_name(ARG_DAILY)
+        .long("daily")
+        .conflicts_with_all(&[ARG_EVIL, ARG_BOARD_WIDTH, ARG_BOARD_HEIGHT])
+        .help("Plays today's challenge: the same tiles for everyone on a 4x4 board"),
+    )
+    .arg(
+      Arg::with_name(ARG_PUZZLE)
+        .long("puzzle")
+        .value_name("FILE")
+        .conflicts_with_all(&[ARG_EVIL, ARG_DAILY])
+        .help("Plays the puzzle in FILE. See puzzles/ for examples."),
+    )
+    .arg(
+      Arg::with_name(ARG_LOAD)
+        .long("load")
+        .value_name("FILE")
+        .conflicts_with_all(&[ARG_EVIL, ARG_DAILY, ARG_PUZZLE])
+        .help("Continues the game saved in FILE (see --edit)"),
+    )
+    .arg(
+      Arg::with_name(ARG_KEYS)
+        .long("keys")
+        .value_name("PRESET|FILE")
+        .help("Key bindings: arrows, wasd, vim, or a config file (default: ~/.game2048_keys if it exists)"),
+    )
+    .arg(
+      Arg::with_name(ARG_NO_CONFIRM)
+        .long("no-confirm")
+        .help("New games and quitting need no confirmation (also: \"confirm off\" in the keys file)"),
+    )
+    .arg(
+      Arg::with_name(ARG_SCRIPT)
+        .long("script")
+        .value_name("FILE")
+        .conflicts_with_all(&[ARG_DAILY, ARG_PUZZLE, ARG_LOAD])
+        .help("Executes the moves in FILE (\"-\" for stdin), e.g. \"LLURD\", and prints a summary"),
+    )
+    .arg(
+      Arg::with_name(ARG_SHOW)
+        .long("show")
+        .help("Shows the game of --script or --api in the display (-d)"),
+    )
+    .arg(
+      Arg::with_name(ARG_SEED)
+        .long("seed")
+        .value_name("N")
+        .requires(ARG_SCRIPT)
+        .help("Makes the new tiles of the script's game the same in each run"),
+    )
+    .arg(
+      Arg::with_name(ARG_API)
+        .long("api")
+        .value_name("PORT")
+        .conflicts_with_all(&[ARG_DAILY, ARG_PUZZLE, ARG_LOAD, ARG_SCRIPT])
+        .help("Serves the game through a JSON API on localhost:PORT, e.g. POST /move/left"),
+    )
+    .arg(
+      Arg::with_name(ARG_ENGINE)
+        .long("engine")
+        .help("Lets a program play through a line-based protocol on stdin and stdout, e.g. \"move left\""),
+    )
+    .arg(
+      Arg::with_name(ARG_EDIT)
+        .long("edit")
+        .help("Sets up a board for playing or saving as a puzzle or save file (ncurses only)"),
+    )
+    .arg(
+      Arg::with_name(ARG_SOLVE)
+        .long("solve")
+        .value_name("FILE")
+        .conflicts_with(ARG_PUZZLE)
+        .help("Prints a shortest solution of the puzzle in FILE and exits. With -e, for any new tiles."),
+    )
+    .arg(
+      Arg::with_name(ARG_NODE_LIMIT)
+        .long("node-limit")
+        .value_name("N")
+        .help("Gives up solving after N positions (default: 1000000)"),
+    )
+    .arg(
+      Arg::with_name(ARG_VERSUS)
+        .long("versus")
+        .help("Two players on a split screen (ncurses only): player 1 moves with letters, player 2 arrows"),
+    )
+    .arg(
+      Arg::with_name(ARG_TARGET)
+        .long("target")
+        .value_name("TILE")
+        .default_value("2048")
+        .help("The tile to reach for winning a versus game"),
+    )
+    .arg(
+      Arg::with_name(ARG_HOST)
+        .long("host")
+        .value_name("PORT")
+        .help("Hosts a race: waits for an opponent on PORT, then both play the same game (ncurses only)"),
+    )
+    .arg(
+      Arg::with_name(ARG_JOIN)
+        .long("join")
+        .value_name("HOST:PORT")
+        .conflicts_with(ARG_HOST)
+        .help("Joins a race hosted at HOST:PORT (ncurses only)"),
+    )
+    .arg(
+      Arg::with_name(ARG_TOURNAMENT)
+        .short("t")
+        .long("tournament")
+        .value_name("STRATEGIES")
+        .use_delimiter(true)
+        .help("Lets the given comma-separated strategies play against each other and exits. See -l."),
+    )
+    .arg(
+      Arg::with_name(ARG_SEEDS)
+        .long("seeds")
+        .value_name("N")
+        .default_value("10")
+        .help("Number of games per strategy in a tournament. Seeds 1 to N are used."),
+    )
+    .arg(
+      Arg::with_name(ARG_CSV)
+        .long("csv")
+        .value_name("FILE")
+        .requires(ARG_TOURNAMENT)
+        .help("Writes the result of each tournament game to FILE (\"-\" for stdout)"),
+    )
     .get_matches();
+  // clap's groups of required arguments would clash with the conflicts of --script and --api
+  if args.is_present(ARG_SHOW) && !args.is_present(ARG_SCRIPT) && !args.is_present(ARG_API) {
+    let message = "--show requires --script or --api";
+    clap::Error::with_description(message, clap::ErrorKind::MissingRequiredArgument).exit();
+  }
 
   if args.is_present(ARG_LIST_VIEW_TYPES) {
-    println!("ncurses");
-    println!("console");
+    println!("display types:");
+    println!("  ncurses");
+    println!("  console");
+    println!("strategies:");
+    for name in game2048::STRATEGY_NAMES.iter() {
+      println!("  {}", name);
+    }
   } else {
     stderrlog::new()
       .module(module_path!())
@@ -56,7 +256,59 @@ fn main() {
       .color(stderrlog::ColorChoice::Always)
       .init()
       .unwrap();
-    if let Some(view_type) = match args.value_of(ARG_VIEW_TYPE) {
+    let spawn_type = if args.is_present(ARG_EVIL) { SpawnType::Evil } else { SpawnType::Random };
+    let (key_map, mut settings) = match KeyMap::find(args.value_of(ARG_KEYS)) {
+      Ok(found) => found,
+      Err(msg) => return println!("{}", msg),
+    };
+    if args.is_present(ARG_NO_CONFIRM) {
+      settings.confirm = false;
+    }
+    let (width, height) = match (board_size(&args, ARG_BOARD_WIDTH), board_size(&args, ARG_BOARD_HEIGHT)) {
+      (Ok(width), Ok(height)) => (width, height),
+      (Err(msg), _) | (_, Err(msg)) => return println!("{}", msg),
+    };
+    if let Some(strategy_names) = args.values_of(ARG_TOURNAMENT) {
+      let seeds = match positive(&args, ARG_SEEDS) {
+        Ok(seeds) => seeds.unwrap(), // has a default value
+        Err(msg) => return println!("{}", msg),
+      };
+      let strategy_names: Vec<&str> = strategy_names.collect();
+      if let Err(msg) =
+        game2048::tournament(&strategy_names, spawn_type, seeds, width, height, args.value_of(ARG_CSV))
+      {
+        println!("{}", msg);
+      }
+    } else if let Some(path) = args.value_of(ARG_SOLVE) {
+      let solve = |node_limit| game2048::solve_puzzle(path, spawn_type, node_limit);
+      if let Err(msg) = positive(&args, ARG_NODE_LIMIT).and_then(solve) {
+        println!("{}", msg);
+      }
+    } else if let Some(port) = args.value_of(ARG_HOST) {
+      match port.parse() {
+        Ok(port) =>
+          if let Err(msg) = game2048::host_race(&key_map, port, width, height) {
+            println!("{}", msg);
+          },
+        Err(_) => println!("Invalid port \"{}\"", port),
+      }
+    } else if let Some(address) = args.value_of(ARG_JOIN) {
+      if let Err(msg) = game2048::join_race(&key_map, address) {
+        println!("{}", msg);
+      }
+    } else if args.is_present(ARG_ENGINE) {
+      game2048::run_engine(spawn_type, width, height);
+    } else if args.is_present(ARG_EDIT) {
+      game2048::edit_board(&key_map, settings, width, height);
+    } else if args.is_present(ARG_VERSUS) {
+      // the target has a default value, and only tiles can be reached
+      match positive::<u16>(&args, ARG_TARGET) {
+        Ok(Some(target)) if target > 1 && target.is_power_of_two() =>
+          game2048::play_versus(&key_map, spawn_type, width, height, target),
+        Ok(_) => println!("Invalid target \"{}\"", args.value_of(ARG_TARGET).unwrap()),
+        Err(msg) => println!("{}", msg),
+      }
+    } else if let Some(view_type) = match args.value_of(ARG_VIEW_TYPE) {
       None | Some("console") => Some(ViewType::Console),
       Some("ncurses") => Some(ViewType::NCurses),
       Some(other) => {
@@ -64,9 +316,76 @@ fn main() {
         None
       },
     } {
-      let width = args.value_of(ARG_BOARD_WIDTH).unwrap().parse().unwrap_or(3);
-      let height = args.value_of(ARG_BOARD_HEIGHT).unwrap().parse().unwrap_or(3);
-      game2048::play(view_type, width, height);
+      if args.is_present(ARG_DAILY) {
+        if let Err(msg) = game2048::play_daily(view_type, &key_map, settings) {
+          println!("{}", msg);
+        }
+      } else if let Some(path) = args.value_of(ARG_LOAD) {
+        if let Err(msg) = game2048::play_saved(view_type, &key_map, settings, path) {
+          println!("{}", msg);
+        }
+      } else if let Some(path) = args.value_of(ARG_SCRIPT) {
+        let view_type = if args.is_present(ARG_SHOW) { Some(view_type) } else { None };
+        match args.value_of(ARG_SEED).map(|seed| seed.parse().map_err(|_| seed)).transpose() {
+          Ok(seed) =>
+            if let Err(msg) = game2048::run_script(path, view_type, spawn_type, seed, width, height) {
+              println!("{}", msg);
+            },
+          Err(seed) => println!("Invalid seed \"{}\"", seed),
+        }
+      } else if let Some(port) = args.value_of(ARG_API) {
+        let view_type = if args.is_present(ARG_SHOW) { Some(view_type) } else { None };
+        match port.parse() {
+          Ok(port) =>
+            if let Err(msg) = game2048::serve_api(port, view_type, spawn_type, width, height) {
+              println!("{}", msg);
+            },
+          Err(_) => println!("Invalid port \"{}\"", port),
+        }
+      } else if let Some(path) = args.value_of(ARG_PUZZLE) {
+        if let Err(msg) = game2048::play_puzzle(view_type, &key_map, settings, path) {
+          println!("{}", msg);
+        }
+      } else {
+        let preview = match args.value_of(ARG_PREVIEW) {
+          Some("value") => Some(Preview::Value),
+          Some("square") => Some(Preview::ValueAndSquare),
+          _ => None,
+        };
+        let limits = (seconds(&args, ARG_TIME_ATTACK), seconds(&args, ARG_TIME), positive(&args, ARG_MOVES));
+        let (spawn_interval, time_limit, move_limit) = match limits {
+          (Ok(spawn_interval), Ok(time_limit), Ok(move_limit)) => (spawn_interval, time_limit, move_limit),
+          (Err(msg), ..) | (_, Err(msg), _) | (.., Err(msg)) => return println!("{}", msg),
+        };
+        let time_limit = time_limit.or_else(|| spawn_interval.map(|_| Duration::from_secs(120)));
+        let rules = Rules { spawn_interval, time_limit, move_limit, ..Rules::default() };
+        if let Err(msg) =
+          game2048::play(view_type, &key_map, settings, spawn_type, preview, rules, width, height)
+        {
+          println!("{}", msg);
+        }
+      }
     }
   }
 }
+
+fn board_size(args: &ArgMatches, arg: &str) -> Result<usize, String> {
+  positive(args, arg).map(Option::unwrap) // has a default value
+}
+
+// the value of `arg`, if given. It must be a number above 0.
+fn positive<T: FromStr + PartialOrd + Default>(args: &ArgMatches, arg: &str) -> Result<Option<T>, String> {
+  match args.value_of(arg) {
+    None => Ok(None),
+    Some(value) => match value.parse() {
+      Ok(number) if number > T::default() => Ok(Some(number)),
+      _ => Err(format!("Invalid {} \"{}\"", arg, value)),
+    },
+  }
+}
+
+// the value of `arg` in seconds, if given. Infinite or too many seconds are invalid, too.
+fn seconds(args: &ArgMatches, arg: &str) -> Result<Option<Duration>, String> {
+  let invalid = || format!("Invalid {} \"{}\"", arg, args.value_of(arg).unwrap_or_default());
+  positive(args, arg)?.map(|seconds| Duration::try_from_secs_f64(seconds).map_err(|_| invalid())).transpose()
+}
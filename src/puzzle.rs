@@ -0,0 +1,168 @@
+// A puzzle is a starting board, a fixed sequence of new tiles, a goal tile, and optionally a move
+// limit. Puzzle files are plain text:
+//
+//   # comment
+//   goal 64
+//   moves 10        (optional)
+//   board           (followed by one line per row. 0 or . is an empty square)
+//   2 2 4 .
+//   . 8 . .
+//   spawns          (optional. VALUE@X,Y or just VALUE for the first empty square)
+//   2@3,0 4 2@0,0
+//
+// X and Y start at 0 in the top left corner. After the last spawn, the sequence starts over.
+
+#[cfg(test)]
+mod test;
+
+use std::fmt;
+use std::fs;
+
+use crate::game::{Board, Spawn, Square, TileValue};
+
+#[derive(Debug, PartialEq)]
+pub struct Puzzle {
+  pub board:      Board,
+  pub spawns:     Vec<Spawn>,
+  pub goal:       TileValue,
+  pub move_limit: Option<usize>
+}
+
+enum Section {
+  Header,
+  Board,
+  Spawns
+}
+
+impl Puzzle {
+  pub fn load(path: &str) -> Result<Puzzle, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    Self::parse(&text).map_err(|e| format!("{}: {}", path, e))
+  }
+
+  pub fn save(&self, path: &str) -> Result<(), String> {
+    fs::write(path, self.to_string()).map_err(|e| format!("Cannot write {}: {}", path, e))
+  }
+
+  pub fn parse(text: &str) -> Result<Puzzle, String> {
+    let mut section = Section::Header;
+    let mut rows: Vec<Vec<Square>> = vec![];
+    let mut spawns = vec![];
+    let mut goal = None;
+    let mut move_limit = None;
+    for (line_index, line) in text.lines().enumerate() {
+      let error = |msg: &str| format!("line {}: {}", line_index + 1, msg);
+      let words: Vec<&str> = line.split_whitespace().collect();
+      match words.as_slice() {
+        [] => (),
+        [comment, ..] if comment.starts_with('#') => (),
+        ["goal", value] => goal = Some(parse_tile(value).ok_or_else(|| error("invalid goal tile"))?),
+        ["moves", value] => move_limit = Some(value.parse().map_err(|_| error("invalid number of moves"))?),
+        ["board"] => section = Section::Board,
+        ["spawns"] => section = Section::Spawns,
+        squares => match section {
+          Section::Header => return Err(error(&format!("unknown keyword \"{}\"", squares[0]))),
+          Section::Board => rows.push(parse_row(squares).ok_or_else(|| error("invalid square"))?),
+          Section::Spawns =>
+            for spawn in squares {
+              let invalid = || error(&format!("invalid spawn \"{}\"", spawn));
+              spawns.push(Self::parse_spawn(spawn).ok_or_else(invalid)?);
+            },
+        }
+      }
+    }
+    let goal = goal.ok_or("missing goal")?;
+    let board = board_from_rows(&rows)?;
+    for spawn in &spawns {
+      match spawn.at {
+        Some((x, y)) if x >= board.size_x() || y >= board.size_y() =>
+          return Err(format!("spawn \"{}@{},{}\" is outside the board", spawn.value, x, y)),
+        _ => ()
+      }
+    }
+    Ok(Puzzle { board, spawns, goal, move_limit })
+  }
+
+  fn parse_spawn(text: &str) -> Option<Spawn> {
+    let mut parts = text.splitn(2, '@');
+    let value = parse_tile(parts.next()?)?;
+    let at = match parts.next() {
+      None => None,
+      Some(coord) => {
+        let mut xy = coord.splitn(2, ',');
+        Some((xy.next()?.parse().ok()?, xy.next()?.parse().ok()?))
+      }
+    };
+    Some(Spawn { value, at })
+  }
+}
+
+impl fmt::Display for Puzzle {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "goal {}", self.goal)?;
+    if let Some(move_limit) = self.move_limit {
+      writeln!(f, "moves {}", move_limit)?;
+    }
+    write_board(f, &self.board)?;
+    if !self.spawns.is_empty() {
+      writeln!(f, "spawns")?;
+      let spawns: Vec<String> = self
+        .spawns
+        .iter()
+        .map(|spawn| match spawn.at {
+          None => spawn.value.to_string(),
+          Some((x, y)) => format!("{}@{},{}", spawn.value, x, y)
+        })
+        .collect();
+      writeln!(f, "{}", spawns.join(" "))?;
+    }
+    Ok(())
+  }
+}
+
+// The board section is shared with save files (see savegame.rs).
+
+// powers of two only
+pub fn parse_tile(text: &str) -> Option<TileValue> {
+  match text.parse::<TileValue>() {
+    Ok(value) if value > 1 && value.is_power_of_two() => Some(value),
+    _ => None
+  }
+}
+
+pub fn parse_row(squares: &[&str]) -> Option<Vec<Square>> {
+  squares
+    .iter()
+    .map(|&square| match square {
+      "0" | "." => Some(Square::Empty),
+      _ => parse_tile(square).map(Square::Value)
+    })
+    .collect()
+}
+
+pub fn board_from_rows(rows: &[Vec<Square>]) -> Result<Board, String> {
+  if rows.is_empty() || rows.iter().any(|row| row.len() != rows[0].len()) {
+    return Err(String::from("the board must have rows of equal length"));
+  }
+  let mut board = Board::new(rows[0].len(), rows.len());
+  for (y, row) in rows.iter().enumerate() {
+    for (x, square) in row.iter().enumerate() {
+      board.put(board.coord(x, y), *square);
+    }
+  }
+  Ok(board)
+}
+
+pub fn write_board(f: &mut fmt::Formatter<'_>, board: &Board) -> fmt::Result {
+  writeln!(f, "board")?;
+  for y in 0..board.size_y() {
+    let row: Vec<String> = (0..board.size_x())
+      .map(|x| match board.at_xy(x, y) {
+        Square::Empty => String::from("."),
+        Square::Value(value) => value.to_string()
+      })
+      .collect();
+    writeln!(f, "{}", row.join(" "))?;
+  }
+  Ok(())
+}
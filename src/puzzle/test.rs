@@ -0,0 +1,70 @@
+use super::Puzzle;
+use crate::game::{Command, Game, GameState, Spawn, Square};
+
+const EXAMPLE: &str = "# example
+goal 16
+moves 3
+board
+8 4 4 .
+. . . .
+spawns
+2@3,1 4
+";
+
+#[test]
+fn parse_example() {
+  let puzzle = Puzzle::parse(EXAMPLE).unwrap();
+  assert_eq!(puzzle.goal, 16);
+  assert_eq!(puzzle.move_limit, Some(3));
+  assert_eq!((puzzle.board.size_x(), puzzle.board.size_y()), (4, 2));
+  assert_eq!(puzzle.board.at_xy(1, 0), Square::Value(4));
+  assert_eq!(puzzle.board.at_xy(1, 1), Square::Empty);
+  assert_eq!(puzzle.spawns, vec![Spawn { value: 2, at: Some((3, 1)) }, Spawn { value: 4, at: None }]);
+}
+
+#[test]
+fn written_puzzle_parses_to_same_puzzle() {
+  let puzzle = Puzzle::parse(EXAMPLE).unwrap();
+  assert_eq!(Puzzle::parse(&puzzle.to_string()).unwrap(), puzzle);
+}
+
+#[test]
+fn goal_is_required() {
+  assert!(Puzzle::parse("board\n2 4\n").is_err());
+}
+
+#[test]
+fn rows_must_have_equal_length() {
+  assert!(Puzzle::parse("goal 8\nboard\n2 4\n2\n").is_err());
+}
+
+#[test]
+fn spawns_must_be_on_the_board() {
+  assert_eq!(Puzzle::parse("goal 8\nboard\n2 4\nspawns\n2@1,0 4@2,0\n").unwrap_err(),
+             "spawn \"4@2,0\" is outside the board");
+  assert!(Puzzle::parse("goal 8\nboard\n2 4\nspawns\n2@0,1\n").is_err());
+}
+
+#[test]
+fn tiles_must_be_powers_of_two() {
+  assert!(Puzzle::parse("goal 8\nboard\n2 3\n").is_err());
+}
+
+#[test]
+fn game_is_won_when_goal_is_reached() {
+  let mut game = Game::from_puzzle(&Puzzle::parse(EXAMPLE).unwrap());
+  game.execute(Command::Left); // 8 8 . . / . . . 2
+  assert!(matches!(game.state(), GameState::Running));
+  game.execute(Command::Left); // 16 . . . / 2 . . .
+  assert!(matches!(game.state(), GameState::Won));
+}
+
+#[test]
+fn game_is_finished_when_moves_are_used_up() {
+  let mut game = Game::from_puzzle(&Puzzle::parse(EXAMPLE).unwrap());
+  game.execute(Command::Down); // . . . . / 8 4 4 2
+  game.execute(Command::Up); // 8 4 4 2 / 4 . . .
+  game.execute(Command::Down); // 8 2 . . / 4 4 4 2
+  assert_eq!(game.moves_left(), Some(0));
+  assert!(matches!(game.state(), GameState::Finished));
+}
@@ -0,0 +1,132 @@
+// Two instances of the game, connected via TCP, play the same seed at the same time. Both sides
+// send a status line after each of their moves:
+//
+//   host → guest:  RACE <seed> <width> <height>
+//   both ways:     STATUS <score> <max tile> <moves> <over: 0 or 1>
+
+#[cfg(test)]
+mod test;
+
+use log::{info, warn};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::ops::RangeInclusive;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::game::{Game, GameState, TileValue};
+
+#[derive(Clone, Default, PartialEq)]
+pub struct OpponentStatus {
+  pub score:        u32,
+  pub max_tile:     TileValue,
+  pub moves:        usize,
+  pub over:         bool,
+  pub disconnected: bool
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RaceSettings {
+  pub seed:   u64,
+  pub size_x: usize,
+  pub size_y: usize
+}
+
+impl RaceSettings {
+  // the board sizes a guest accepts from a host
+  pub const SIZES: RangeInclusive<usize> = 2..=16;
+
+  // None, unless `line` is a greeting with a board size in SIZES
+  fn parse(line: &str) -> Option<RaceSettings> {
+    match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+      ["RACE", seed, size_x, size_y] => {
+        let (size_x, size_y) = (size_x.parse().ok()?, size_y.parse().ok()?);
+        if !Self::SIZES.contains(&size_x) || !Self::SIZES.contains(&size_y) {
+          return None;
+        }
+        Some(RaceSettings { seed: seed.parse().ok()?, size_x, size_y })
+      },
+      _ => None
+    }
+  }
+}
+
+// the greeting sent by the host
+impl fmt::Display for RaceSettings {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "RACE {} {} {}", self.seed, self.size_x, self.size_y)
+  }
+}
+
+pub struct Race {
+  stream:   TcpStream,
+  opponent: Arc<Mutex<OpponentStatus>>
+}
+
+impl Race {
+  // waits for a guest and sends the settings
+  pub fn host(port: u16, settings: &RaceSettings) -> io::Result<Race> {
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    let (mut stream, address) = listener.accept()?;
+    info!("Opponent connected from {}", address);
+    writeln!(stream, "{}", settings)?;
+    Ok(Self::start(stream))
+  }
+
+  // connects to a host and receives the settings
+  pub fn join<A: ToSocketAddrs>(address: A) -> io::Result<(Race, RaceSettings)> {
+    let stream = TcpStream::connect(address)?;
+    let mut line = String::new();
+    BufReader::new(stream.try_clone()?).read_line(&mut line)?;
+    let settings = RaceSettings::parse(&line).ok_or_else(|| {
+                     io::Error::new(io::ErrorKind::InvalidData, format!("invalid greeting: {}", line.trim()))
+                   })?;
+    Ok((Self::start(stream), settings))
+  }
+
+  // The opponent's status is received in a background thread.
+  fn start(stream: TcpStream) -> Race {
+    let opponent = Arc::new(Mutex::new(OpponentStatus::default()));
+    let reader = BufReader::new(stream.try_clone().expect("cannot clone TCP stream"));
+    let receiver_status = Arc::clone(&opponent);
+    thread::spawn(move || {
+      for line in reader.lines() {
+        match line {
+          Ok(line) => match Self::parse_status(&line) {
+            Some(status) => *receiver_status.lock().unwrap() = status,
+            None => warn!("Ignoring unknown message from opponent: {}", line)
+          },
+          Err(e) => {
+            warn!("Connection to opponent failed: {}", e);
+            break;
+          }
+        }
+      }
+      receiver_status.lock().unwrap().disconnected = true;
+    });
+    Race { stream, opponent }
+  }
+
+  fn parse_status(line: &str) -> Option<OpponentStatus> {
+    match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+      ["STATUS", score, max_tile, moves, over] => Some(OpponentStatus { score:        score.parse().ok()?,
+                                                                        max_tile:     max_tile.parse().ok()?,
+                                                                        moves:        moves.parse().ok()?,
+                                                                        over:         *over == "1",
+                                                                        disconnected: false }),
+      _ => None
+    }
+  }
+
+  pub fn send_status(&self, game: &Game) {
+    let max_tile = game.board.max_tile().map_or(0, |(_, value)| value);
+    let over = if let GameState::Over = game.state() { 1 } else { 0 };
+    let status = format!("STATUS {} {} {} {}", game.score(), max_tile, game.shift_count(), over);
+    if let Err(e) = writeln!(&self.stream, "{}", status) {
+      warn!("Cannot send status to opponent: {}", e);
+    }
+  }
+
+  pub fn opponent(&self) -> OpponentStatus { self.opponent.lock().unwrap().clone() }
+}
@@ -0,0 +1,19 @@
+use super::{Race, RaceSettings};
+
+#[test]
+fn greeting_is_parsed_back() {
+  let settings = RaceSettings { seed: 42, size_x: 4, size_y: 3 };
+  assert_eq!(RaceSettings::parse(&format!("{}\n", settings)), Some(settings));
+  assert_eq!(RaceSettings::parse("RACE 1 0 0"), None);
+  assert_eq!(RaceSettings::parse("RACE 1 4 17"), None);
+  assert_eq!(RaceSettings::parse("HELLO"), None);
+}
+
+#[test]
+fn parse_status() {
+  let status = Race::parse_status("STATUS 120 32 15 1").unwrap();
+  assert_eq!((status.score, status.max_tile, status.moves, status.over), (120, 32, 15, true));
+  assert!(!status.disconnected);
+  assert!(Race::parse_status("STATUS 120 32").is_none());
+  assert!(Race::parse_status("STATUS x 32 15 0").is_none());
+}
@@ -0,0 +1,59 @@
+// A game saved for continuing later. Save files are plain text, like puzzle files (see puzzle.rs), but
+// with a score instead of a goal, and without spawns, since new tiles are random:
+//
+//   score 1234
+//   board
+//   2 4 . .
+//   . 8 . .
+
+#[cfg(test)]
+mod test;
+
+use std::fmt;
+use std::fs;
+
+use crate::game::{Board, Square};
+use crate::puzzle;
+
+#[derive(Debug, PartialEq)]
+pub struct SaveGame {
+  pub board: Board,
+  pub score: u32
+}
+
+impl SaveGame {
+  pub fn load(path: &str) -> Result<SaveGame, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    Self::parse(&text).map_err(|e| format!("{}: {}", path, e))
+  }
+
+  pub fn save(&self, path: &str) -> Result<(), String> {
+    fs::write(path, self.to_string()).map_err(|e| format!("Cannot write {}: {}", path, e))
+  }
+
+  pub fn parse(text: &str) -> Result<SaveGame, String> {
+    let mut in_board = false;
+    let mut rows: Vec<Vec<Square>> = vec![];
+    let mut score = 0;
+    for (line_index, line) in text.lines().enumerate() {
+      let error = |msg: &str| format!("line {}: {}", line_index + 1, msg);
+      let words: Vec<&str> = line.split_whitespace().collect();
+      match words.as_slice() {
+        [] => (),
+        [comment, ..] if comment.starts_with('#') => (),
+        ["score", value] if !in_board => score = value.parse().map_err(|_| error("invalid score"))?,
+        ["board"] => in_board = true,
+        squares if in_board => rows.push(puzzle::parse_row(squares).ok_or_else(|| error("invalid square"))?),
+        squares => return Err(error(&format!("unknown keyword \"{}\"", squares[0])))
+      }
+    }
+    Ok(SaveGame { board: puzzle::board_from_rows(&rows)?, score })
+  }
+}
+
+impl fmt::Display for SaveGame {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "score {}", self.score)?;
+    puzzle::write_board(f, &self.board)
+  }
+}
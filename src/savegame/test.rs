@@ -0,0 +1,10 @@
+use super::SaveGame;
+use crate::game::Square;
+
+#[test]
+fn written_save_game_parses_to_same_save_game() {
+  let save_game = SaveGame::parse("score 36\nboard\n2 . 32\n. . 2\n").unwrap();
+  assert_eq!(save_game.score, 36);
+  assert_eq!(save_game.board.at_xy(2, 0), Square::Value(32));
+  assert_eq!(SaveGame::parse(&save_game.to_string()).unwrap(), save_game);
+}
@@ -0,0 +1,45 @@
+// Move scripts drive a game without a player. Each line holds either a command word, or a sequence of
+// direction letters:
+//
+//   # comment
+//   LLURD           (left, left, up, right, down. Spaces are ignored)
+//   new             (also: left, right, up, down, hint, shuffle, quit)
+//
+// Letters and words are case-insensitive.
+
+#[cfg(test)]
+mod test;
+
+use crate::game::Command;
+
+// the commands of one line. Empty for blank lines and comments.
+pub fn parse_line(line: &str) -> Result<Vec<Command>, String> {
+  let line = line.trim();
+  if line.is_empty() || line.starts_with('#') {
+    return Ok(vec![]);
+  }
+  let command = match line.to_lowercase().as_str() {
+    "left" => Some(Command::Left),
+    "right" => Some(Command::Right),
+    "up" => Some(Command::Up),
+    "down" => Some(Command::Down),
+    "hint" => Some(Command::Hint),
+    "shuffle" => Some(Command::Shuffle),
+    "new" => Some(Command::New),
+    "quit" => Some(Command::Quit),
+    _ => None
+  };
+  if let Some(command) = command {
+    return Ok(vec![command]);
+  }
+  line.chars()
+      .filter(|c| !c.is_whitespace())
+      .map(|c| match c.to_ascii_uppercase() {
+        'L' => Ok(Command::Left),
+        'R' => Ok(Command::Right),
+        'U' => Ok(Command::Up),
+        'D' => Ok(Command::Down),
+        _ => Err(format!("unknown command \"{}\"", line))
+      })
+      .collect()
+}
@@ -0,0 +1,11 @@
+use super::parse_line;
+use crate::game::Command;
+
+#[test]
+fn lines_hold_letters_or_words() {
+  let commands = parse_line("lLu d").unwrap();
+  assert!(matches!(commands[..], [Command::Left, Command::Left, Command::Up, Command::Down]));
+  assert!(matches!(parse_line(" New ").unwrap()[..], [Command::New]));
+  assert!(parse_line("# LLR").unwrap().is_empty());
+  assert_eq!(parse_line("jump").unwrap_err(), "unknown command \"jump\"");
+}
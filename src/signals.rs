@@ -0,0 +1,102 @@
+// Lets interactive games end gracefully, so that the terminal is restored and the game can be saved:
+// SIGINT and SIGTERM only set a flag, which the controllers poll while they wait for input. A blocking
+// read is interrupted by them (no SA_RESTART). A panic hook restores the terminal before the panic
+// message is printed, so that the message is not swallowed by the curses screen.
+//
+// ncurses suspends and resumes by itself on SIGTSTP and SIGCONT. The console controller reads keys in raw
+// mode, which `suspend_in_cooked_mode()` takes care of.
+
+use log::warn;
+use ncurses as nc;
+use std::io;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Once, OnceLock};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static CURSES_ACTIVE: AtomicBool = AtomicBool::new(false); // between initscr and endwin of the ncurses view
+static ORIGINAL_TERMINAL: OnceLock<libc::termios> = OnceLock::new(); // the mode before any game started
+static CATCH: Once = Once::new();
+static SUSPEND: Once = Once::new();
+
+// Installs the handlers for SIGINT and SIGTERM and the panic hook. Must be called before ncurses is
+// initialized, which would otherwise install handlers of its own that just end the program.
+pub fn catch() {
+  CATCH.call_once(|| {
+    if let Some(terminal) = terminal_mode() {
+      let _ = ORIGINAL_TERMINAL.set(terminal);
+    }
+    install(libc::SIGINT, interrupt);
+    install(libc::SIGTERM, interrupt);
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+      restore_terminal();
+      default_hook(info);
+    }));
+  });
+}
+
+// Restores the terminal mode before stopping on SIGTSTP, and the mode at the time of stopping on SIGCONT.
+pub fn suspend_in_cooked_mode() {
+  catch();
+  SUSPEND.call_once(|| install(libc::SIGTSTP, suspend));
+}
+
+// true, after SIGINT or SIGTERM
+pub fn interrupted() -> bool { INTERRUPTED.load(Ordering::SeqCst) }
+
+// called by the ncurses view when it starts and ends ncurses
+pub fn set_curses_active(active: bool) { CURSES_ACTIVE.store(active, Ordering::SeqCst); }
+
+// ends ncurses, if it is active, and resets the terminal to the mode before the game
+pub fn restore_terminal() {
+  if CURSES_ACTIVE.load(Ordering::SeqCst) && !nc::isendwin() {
+    nc::endwin();
+  }
+  if let Some(terminal) = ORIGINAL_TERMINAL.get() {
+    unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, terminal) };
+  }
+}
+
+fn terminal_mode() -> Option<libc::termios> {
+  let mut terminal = unsafe { std::mem::zeroed::<libc::termios>() };
+  if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut terminal) } == 0 {
+    Some(terminal)
+  } else {
+    None
+  }
+}
+
+fn install(signal: libc::c_int, handler: extern "C" fn(libc::c_int)) {
+  unsafe {
+    let mut action = std::mem::zeroed::<libc::sigaction>();
+    action.sa_sigaction = handler as libc::sighandler_t;
+    libc::sigemptyset(&mut action.sa_mask);
+    if libc::sigaction(signal, &action, std::ptr::null_mut()) != 0 {
+      warn!("Cannot handle signal {}: {}", signal, io::Error::last_os_error());
+    }
+  }
+}
+
+extern "C" fn interrupt(_signal: libc::c_int) { INTERRUPTED.store(true, Ordering::SeqCst); }
+
+// only uses async-signal-safe functions: tcgetattr, tcsetattr, signal, raise and sigprocmask
+extern "C" fn suspend(signal: libc::c_int) {
+  let mode = terminal_mode();
+  if let Some(terminal) = ORIGINAL_TERMINAL.get() {
+    unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, terminal) };
+  }
+  unsafe {
+    libc::signal(signal, libc::SIG_DFL);
+    libc::raise(signal); // pending, since the signal is blocked while its handler runs
+    let mut signals = std::mem::zeroed::<libc::sigset_t>();
+    libc::sigemptyset(&mut signals);
+    libc::sigaddset(&mut signals, signal);
+    libc::sigprocmask(libc::SIG_UNBLOCK, &signals, std::ptr::null_mut()); // stops here until SIGCONT
+    let handler: extern "C" fn(libc::c_int) = suspend;
+    libc::signal(signal, handler as libc::sighandler_t);
+  }
+  if let Some(mode) = mode {
+    unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &mode) };
+  }
+}
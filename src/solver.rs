@@ -0,0 +1,214 @@
+// Searches for the shortest sequence of moves that reaches a puzzle's goal tile. New tiles either follow
+// the puzzle's spawn sequence, or they are placed by an adversary. In the latter case a solution has to
+// work for every square and value (2 or 4) the adversary may choose; the line returned is the one in
+// which the adversary resists longest.
+
+#[cfg(test)]
+mod test;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::game::{Board, Direction, Spawn, Square, TileValue};
+use crate::puzzle::Puzzle;
+
+pub const DEFAULT_NODE_LIMIT: usize = 1_000_000;
+
+pub enum Spawns {
+  Sequence,
+  WorstCase
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Step {
+  pub direction: Direction,
+  pub new_tile:  (usize, usize, TileValue)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+  Solved(Vec<Step>),
+  Unsolvable, // within the move limit, if the puzzle has one
+  NodeLimitReached
+}
+
+struct NodeLimitReached;
+
+pub struct Solver<'a> {
+  puzzle:     &'a Puzzle,
+  spawns:     Spawns,
+  node_limit: usize,
+  nodes:      usize,
+  // worst case only: canonical board => None, if the goal cannot be forced at all, or Some(n), if it
+  // cannot be forced within n moves
+  failed:     HashMap<Board, Option<usize>>,
+  cut_off:    bool // worst case only: the current search ran out of moves somewhere
+}
+
+impl<'a> Solver<'a> {
+  pub fn new(puzzle: &'a Puzzle, spawns: Spawns, node_limit: usize) -> Self {
+    Solver { puzzle, spawns, node_limit, nodes: 0, failed: HashMap::new(), cut_off: false }
+  }
+
+  // number of positions searched so far
+  pub fn nodes(&self) -> usize {
+    self.nodes
+  }
+
+  pub fn solve(&mut self) -> Outcome {
+    match self.spawns {
+      Spawns::Sequence => self.solve_sequence(),
+      Spawns::WorstCase => self.solve_worst_case(),
+    }
+  }
+
+  // Breadth-first search: the first solution found is a shortest one. Symmetric boards cannot be merged
+  // here, because the spawn sequence places tiles on fixed squares.
+  fn solve_sequence(&mut self) -> Outcome {
+    let spawns = match self.puzzle.spawns.len() {
+      0 => vec![Spawn { value: 2, at: None }],
+      _ => self.puzzle.spawns.clone(),
+    };
+    let start = self.puzzle.board.clone();
+    if self.goal_reached(&start) {
+      return Outcome::Solved(vec![]);
+    }
+    // steps found so far, each with the index of the step before it
+    let mut steps: Vec<(Option<usize>, Step)> = vec![];
+    let mut seen = HashSet::new();
+    seen.insert((start.clone(), 0));
+    let mut queue = VecDeque::new();
+    queue.push_back((start, 0, 0, None));
+    while let Some((board, next_spawn, moves, last_step)) = queue.pop_front() {
+      if let Some(limit) = self.puzzle.move_limit {
+        let spawned: u32 =
+          (0..limit - moves).map(|i| spawns[(next_spawn + i) % spawns.len()].value as u32).sum();
+        // no tile can grow larger than the sum of all tiles
+        if moves == limit || board.tile_sum() + spawned < self.puzzle.goal as u32 {
+          continue;
+        }
+      }
+      for &direction in Direction::ALL.iter() {
+        let mut child = board.clone();
+        if child.shift_single_threaded(direction).is_none() {
+          continue;
+        }
+        let (coord, value) = spawns[next_spawn].place(&child);
+        child.put(coord, Square::Value(value));
+        let child_spawn = (next_spawn + 1) % spawns.len();
+        if !seen.insert((child.clone(), child_spawn)) {
+          continue;
+        }
+        self.nodes += 1;
+        steps.push((last_step, Step { direction, new_tile: (coord.x, coord.y, value) }));
+        if self.goal_reached(&child) {
+          let last = steps.len() - 1;
+          return Outcome::Solved(Self::line_ending_with(steps, last));
+        }
+        if self.nodes >= self.node_limit {
+          return Outcome::NodeLimitReached;
+        }
+        queue.push_back((child, child_spawn, moves + 1, Some(steps.len() - 1)));
+      }
+    }
+    Outcome::Unsolvable
+  }
+
+  fn line_ending_with(mut steps: Vec<(Option<usize>, Step)>, last: usize) -> Vec<Step> {
+    let mut indices = vec![last];
+    while let Some(previous) = steps[*indices.last().unwrap()].0 {
+      indices.push(previous);
+    }
+    let mut line = vec![];
+    for index in indices {
+      line.push(steps.swap_remove(index).1); // indices decrease, so earlier ones stay valid
+    }
+    line.reverse();
+    line
+  }
+
+  // Iterative deepening: tries to force the goal within 0, 1, 2, … moves.
+  fn solve_worst_case(&mut self) -> Outcome {
+    let start = self.puzzle.board.clone();
+    let mut moves = 0;
+    loop {
+      self.cut_off = false;
+      match self.force(&start, moves) {
+        Ok(Some(line)) => return Outcome::Solved(line),
+        Err(NodeLimitReached) => return Outcome::NodeLimitReached,
+        Ok(None) if !self.cut_off || Some(moves) == self.puzzle.move_limit => return Outcome::Unsolvable,
+        Ok(None) => moves += 1,
+      }
+    }
+  }
+
+  // The steps of the adversary's longest resistance, if the goal can be forced within `moves` moves.
+  fn force(&mut self, board: &Board, moves: usize) -> Result<Option<Vec<Step>>, NodeLimitReached> {
+    if self.goal_reached(board) {
+      return Ok(Some(vec![]));
+    }
+    // the adversary may always choose a 2, and no tile can grow larger than the sum of all tiles
+    if moves == 0 || board.tile_sum() + 2 * (moves as u32) < self.puzzle.goal as u32 {
+      self.cut_off = true;
+      return Ok(None);
+    }
+    // symmetric boards are equally hard, since the adversary may choose any square
+    let (canonical, _) = board.canonical();
+    match self.failed.get(&canonical) {
+      Some(None) => return Ok(None),
+      Some(Some(failed_moves)) if *failed_moves >= moves => {
+        self.cut_off = true;
+        return Ok(None);
+      },
+      _ => (),
+    }
+    let cut_off_before = self.cut_off;
+    self.cut_off = false;
+    for &direction in Direction::ALL.iter() {
+      let mut shifted = board.clone();
+      if shifted.shift_single_threaded(direction).is_none() {
+        continue;
+      }
+      if let Some(line) = self.force_after_shift(&shifted, direction, moves)? {
+        self.cut_off = cut_off_before;
+        return Ok(Some(line));
+      }
+    }
+    self.failed.insert(canonical, if self.cut_off { Some(moves) } else { None });
+    self.cut_off |= cut_off_before;
+    Ok(None)
+  }
+
+  fn force_after_shift(&mut self, shifted: &Board, direction: Direction, moves: usize)
+                       -> Result<Option<Vec<Step>>, NodeLimitReached> {
+    let mut longest: Option<Vec<Step>> = None;
+    for x in 0..shifted.size_x() {
+      for y in 0..shifted.size_y() {
+        if shifted.at_xy(x, y) != Square::Empty {
+          continue;
+        }
+        for &value in [2, 4].iter() {
+          let mut child = shifted.clone();
+          child.put(child.coord(x, y), Square::Value(value));
+          if self.nodes >= self.node_limit {
+            return Err(NodeLimitReached);
+          }
+          self.nodes += 1;
+          match self.force(&child, moves - 1)? {
+            None => return Ok(None),
+            Some(rest) =>
+              if longest.as_ref().map_or(true, |line| rest.len() >= line.len()) {
+                let mut line = vec![Step { direction, new_tile: (x, y, value) }];
+                line.extend(rest);
+                longest = Some(line);
+              },
+          }
+        }
+      }
+    }
+    Ok(longest)
+  }
+
+  fn goal_reached(&self, board: &Board) -> bool {
+    board.max_tile().is_some_and(|(_, value)| value >= self.puzzle.goal)
+  }
+}
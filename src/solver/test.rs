@@ -0,0 +1,52 @@
+use super::{Outcome, Solver, Spawns, DEFAULT_NODE_LIMIT};
+use crate::game::Direction::*;
+use crate::puzzle::Puzzle;
+
+const FIRST_64: &str = "goal 64
+board
+32 16 8 8
+.  .  .  2
+.  .  .  .
+.  .  .  .
+spawns
+2@3,1 2@3,0 4@0,3 2
+";
+
+fn directions(outcome: Outcome) -> Vec<crate::game::Direction> {
+  match outcome {
+    Outcome::Solved(line) => line.into_iter().map(|step| step.direction).collect(),
+    other => panic!("not solved: {:?}", other),
+  }
+}
+
+#[test]
+fn shortest_solution_with_spawn_sequence() {
+  let puzzle = Puzzle::parse(FIRST_64).unwrap();
+  assert_eq!(directions(Solver::new(&puzzle, Spawns::Sequence, DEFAULT_NODE_LIMIT).solve()),
+             vec![Left, Left, Left]);
+}
+
+#[test]
+fn move_limit_too_low() {
+  let puzzle = Puzzle::parse(&format!("moves 2\n{}", FIRST_64)).unwrap();
+  assert_eq!(Solver::new(&puzzle, Spawns::Sequence, DEFAULT_NODE_LIMIT).solve(), Outcome::Unsolvable);
+}
+
+#[test]
+fn node_limit() {
+  let puzzle = Puzzle::parse(FIRST_64).unwrap();
+  assert_eq!(Solver::new(&puzzle, Spawns::Sequence, 2).solve(), Outcome::NodeLimitReached);
+}
+
+#[test]
+fn worst_case() {
+  let puzzle = Puzzle::parse("goal 16\nboard\n8 4 4\n. . .\n").unwrap();
+  let mut solver = Solver::new(&puzzle, Spawns::WorstCase, DEFAULT_NODE_LIMIT);
+  assert_eq!(directions(solver.solve()).len(), 2);
+}
+
+#[test]
+fn worst_case_stuck_board() {
+  let puzzle = Puzzle::parse("goal 16\nboard\n2 4 8\n4 8 2\n").unwrap();
+  assert_eq!(Solver::new(&puzzle, Spawns::WorstCase, DEFAULT_NODE_LIMIT).solve(), Outcome::Unsolvable);
+}
@@ -0,0 +1,68 @@
+mod corner;
+mod evil;
+mod greedy;
+mod heuristic;
+mod random;
+
+pub use self::corner::Corner;
+pub use self::evil::Evil;
+pub use self::greedy::Greedy;
+pub use self::heuristic::Heuristic;
+pub use self::random::Random;
+
+use crate::game::{Adviser, Board, Direction, Hint, Move};
+
+pub const NAMES: [&str; 4] = ["corner", "greedy", "heuristic", "random"];
+
+// the strategy asked for hints
+const HINT_STRATEGY: &str = "heuristic";
+
+// An automatic player. Given a board, it rates each possible direction; the best rated direction is
+// played.
+pub trait Strategy {
+  fn name(&self) -> &'static str;
+
+  // None, if shifting in `direction` is not possible
+  fn rate(&mut self, board: &Board, direction: Direction) -> Option<f64>;
+
+  fn choose(&mut self, board: &Board) -> Option<Direction> {
+    let mut best: Option<(Direction, f64)> = None;
+    for &direction in Direction::ALL.iter() {
+      if let Some(rating) = self.rate(board, direction) {
+        match best {
+          Some((_, best_rating)) if best_rating >= rating => (),
+          _ => best = Some((direction, rating))
+        }
+      }
+    }
+    best.map(|(direction, _)| direction)
+  }
+}
+
+// `seed` is only used by strategies that make random decisions
+pub fn by_name(name: &str, seed: u64) -> Option<Box<dyn Strategy>> {
+  match name {
+    "corner" => Some(Box::new(Corner::new())),
+    "greedy" => Some(Box::new(Greedy::new())),
+    "heuristic" => Some(Box::new(Heuristic::new())),
+    "random" => Some(Box::new(Random::new(seed))),
+    _ => None
+  }
+}
+
+// Hints for the player (see Game::set_adviser): the direction chosen by the hint strategy, and its rating of
+// each direction.
+pub fn adviser() -> Adviser {
+  let mut strategy = by_name(HINT_STRATEGY, 0).unwrap();
+  Box::new(move |board| {
+    let ratings =
+      Direction::ALL.iter().map(|&direction| (direction, strategy.rate(board, direction))).collect();
+    Hint { direction: strategy.choose(board), ratings }
+  })
+}
+
+// The board after shifting (without a new tile), together with the resulting moves
+fn shifted(board: &Board, direction: Direction) -> Option<(Board, Vec<Move>)> {
+  let mut board = board.clone();
+  board.shift_single_threaded(direction).map(|moves| (board, moves))
+}
@@ -0,0 +1,23 @@
+use super::Strategy;
+use crate::game::{Board, Direction};
+
+// Keeps the tiles in the bottom left corner: prefers Down over Left over Right over Up.
+pub struct Corner {}
+
+impl Corner {
+  pub fn new() -> Self { Corner {} }
+}
+
+impl Strategy for Corner {
+  fn name(&self) -> &'static str { "corner" }
+
+  fn rate(&mut self, board: &Board, direction: Direction) -> Option<f64> {
+    super::shifted(board, direction)?;
+    Some(match direction {
+      Direction::Down => 4.0,
+      Direction::Left => 3.0,
+      Direction::Right => 2.0,
+      Direction::Up => 1.0
+    })
+  }
+}
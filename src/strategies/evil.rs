@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod test;
+
+use super::{Heuristic, Strategy};
+use crate::game::{Board, Coord, Direction, Spawner, Square, TileValue};
+
+// An adversary placing new tiles: it tries every empty square with a 2 and a 4, and picks the tile
+// after which the player's best move is rated lowest by the player's strategy.
+pub struct Evil {
+  player: Box<dyn Strategy>
+}
+
+impl Evil {
+  pub fn new() -> Self { Evil { player: Box::new(Heuristic::new()) } }
+
+  // rating of the best move on `board`, or -∞ if the game is over
+  fn best_rating(&mut self, board: &Board) -> f64 {
+    let player = &mut self.player;
+    Direction::ALL
+      .iter()
+      .filter_map(|&direction| player.rate(board, direction))
+      .fold(f64::NEG_INFINITY, f64::max)
+  }
+}
+
+impl Spawner for Evil {
+  fn spawn(&mut self, board: &Board) -> (Coord, TileValue) {
+    let mut worst: Option<(Coord, TileValue, f64)> = None;
+    for x in 0..board.size_x() {
+      for y in 0..board.size_y() {
+        if let Square::Empty = board.at_xy(x, y) {
+          for &value in [2, 4].iter() {
+            let mut candidate = board.clone();
+            candidate.put(board.coord(x, y), Square::Value(value));
+            let rating = self.best_rating(&candidate);
+            match worst {
+              Some((_, _, worst_rating)) if worst_rating <= rating => (),
+              _ => worst = Some((board.coord(x, y), value, rating))
+            }
+          }
+        }
+      }
+    }
+    let (coord, value, _) = worst.unwrap(); // there is at least one empty square
+    (coord, value)
+  }
+}
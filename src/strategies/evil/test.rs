@@ -0,0 +1,28 @@
+use super::Evil;
+use crate::game::{Board, Direction, Spawner, Square};
+use crate::strategies::{Heuristic, Strategy};
+
+#[test]
+fn evil_ends_the_game_if_it_can() {
+  // a 4 below the 4 could be merged, a 2 cannot
+  let board = Board::with_rows(&[[4, 2], [0, 8]]);
+  let (at, value) = Evil::new().spawn(&board);
+  assert_eq!((at, value), (board.coord(0, 1), 2));
+}
+
+#[test]
+fn evil_picks_the_worst_square_and_value() {
+  let board = Board::with_rows(&[[2, 4], [0, 0]]);
+  let best_rating = |x, y, value| {
+    let mut candidate = board.clone();
+    candidate.put(board.coord(x, y), Square::Value(value));
+    let mut player = Heuristic::new();
+    Direction::ALL.iter().filter_map(|&direction| player.rate(&candidate, direction)).fold(f64::MIN, f64::max)
+  };
+  let (at, value) = Evil::new().spawn(&board);
+  for &(x, y, other) in [(0, 1, 2), (0, 1, 4), (1, 1, 2), (1, 1, 4)].iter() {
+    assert!(best_rating(at.x, at.y, value) <= best_rating(x, y, other));
+  }
+  // a 4 below the 2 and a 2 below the 4 leave no merges. The first one found is taken.
+  assert_eq!((at, value), (board.coord(0, 1), 4));
+}
@@ -0,0 +1,20 @@
+use super::Strategy;
+use crate::game::{Board, Direction, Move};
+
+// Maximizes the points gained by merges in a single move. Ties are broken by the number of empty
+// squares.
+pub struct Greedy {}
+
+impl Greedy {
+  pub fn new() -> Self { Greedy {} }
+}
+
+impl Strategy for Greedy {
+  fn name(&self) -> &'static str { "greedy" }
+
+  fn rate(&mut self, board: &Board, direction: Direction) -> Option<f64> {
+    let (board, moves) = super::shifted(board, direction)?;
+    let points: u32 = moves.iter().map(Move::points).sum();
+    Some(points as f64 + board.num_empty_squares() as f64 / 100.0)
+  }
+}
@@ -0,0 +1,29 @@
+use super::Strategy;
+use crate::game::{Board, Direction, Move};
+
+// Rates the board after the move by a weighted sum of board features: many empty squares and
+// possible merges are good, non-monotonic and rough rows and columns are bad.
+pub struct Heuristic {}
+
+impl Heuristic {
+  const EMPTY_WEIGHT: f64 = 2.7;
+  const MERGE_WEIGHT: f64 = 1.0;
+  const MONOTONICITY_WEIGHT: f64 = 1.0;
+  const SMOOTHNESS_WEIGHT: f64 = 0.1;
+
+  pub fn new() -> Self { Heuristic {} }
+}
+
+impl Strategy for Heuristic {
+  fn name(&self) -> &'static str { "heuristic" }
+
+  fn rate(&mut self, board: &Board, direction: Direction) -> Option<f64> {
+    let (board, moves) = super::shifted(board, direction)?;
+    let points: u32 = moves.iter().map(Move::points).sum();
+    Some((points as f64).log2().max(0.0)
+         + Self::EMPTY_WEIGHT * board.num_empty_squares() as f64
+         + Self::MERGE_WEIGHT * board.num_merges() as f64
+         - Self::MONOTONICITY_WEIGHT * board.monotonicity() as f64
+         - Self::SMOOTHNESS_WEIGHT * board.smoothness() as f64)
+  }
+}
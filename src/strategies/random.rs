@@ -0,0 +1,24 @@
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+use super::Strategy;
+use crate::game::{Board, Direction};
+
+// Plays any possible direction. Useful as a baseline for the other strategies.
+pub struct Random {
+  rng: XorShiftRng
+}
+
+impl Random {
+  pub fn new(seed: u64) -> Self {
+    Random { rng: XorShiftRng::from_seed([seed as u32, (seed >> 32) as u32, 0x7a3d_0f11, 0x5bd1_e995]) }
+  }
+}
+
+impl Strategy for Random {
+  fn name(&self) -> &'static str { "random" }
+
+  fn rate(&mut self, board: &Board, direction: Direction) -> Option<f64> {
+    super::shifted(board, direction)?;
+    Some(self.rng.gen())
+  }
+}
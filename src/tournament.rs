@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod test;
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::ops::Range;
+
+use crate::game::{Game, GameState, TileValue};
+use crate::strategies;
+use crate::SpawnType;
+
+// Every strategy plays one game per seed. Games with the same seed get the same sequence of random
+// numbers for new tiles, so results of different strategies are comparable seed by seed.
+pub struct Tournament {
+  strategy_names: Vec<String>,
+  spawn_type:     SpawnType,
+  seeds:          Range<u64>,
+  size_x:         usize,
+  size_y:         usize
+}
+
+pub struct GameResult {
+  pub strategy: &'static str,
+  pub seed:     u64,
+  pub score:    u32,
+  pub max_tile: TileValue,
+  pub moves:    usize
+}
+
+impl Tournament {
+  pub fn new(strategy_names: &[&str], spawn_type: SpawnType, seeds: Range<u64>, size_x: usize,
+             size_y: usize)
+             -> Result<Self, String> {
+    for name in strategy_names {
+      if strategies::by_name(name, 0).is_none() {
+        return Err(format!("Unknown strategy \"{}\". Available strategies: {}",
+                           name,
+                           strategies::NAMES.join(", ")));
+      }
+    }
+    Ok(Tournament { strategy_names: strategy_names.iter().map(|name| name.to_string()).collect(),
+                    spawn_type,
+                    seeds,
+                    size_x,
+                    size_y })
+  }
+
+  pub fn run(&self) -> Vec<GameResult> {
+    let mut results = Vec::new();
+    for seed in self.seeds.clone() {
+      for name in &self.strategy_names {
+        results.push(self.play(name, seed));
+      }
+    }
+    results
+  }
+
+  fn play(&self, strategy_name: &str, seed: u64) -> GameResult {
+    let mut strategy = strategies::by_name(strategy_name, seed).unwrap(); // names are checked in new()
+    let mut game = Game::new(self.size_x, self.size_y, self.spawn_type.spawner(Some(seed)));
+    while let GameState::Running = game.state() {
+      match strategy.choose(&game.board) {
+        Some(direction) => game.execute(direction.into()),
+        None => break
+      }
+    }
+    GameResult { strategy: strategy.name(),
+                 seed,
+                 score: game.score(),
+                 max_tile: game.board.max_tile().map_or(0, |(_, value)| value),
+                 moves: game.shift_count() }
+  }
+
+  pub fn write_table(&self, results: &[GameResult], out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out,
+             "{:<10} {:>6} {:>6} {:>10} {:>10} {:>9} {:>9} {:>9}",
+             "strategy", "games", "wins", "avg score", "max score", "avg tile", "max tile", "avg moves")?;
+    for name in &self.strategy_names {
+      let own: Vec<&GameResult> = results.iter().filter(|r| r.strategy == name).collect();
+      let games = own.len().max(1) as f64;
+      // a win is a seed on which no other strategy scored more
+      let wins = own.iter()
+                    .filter(|mine| {
+                      results.iter().all(|other| other.seed != mine.seed || other.score <= mine.score)
+                    })
+                    .count();
+      writeln!(out,
+               "{:<10} {:>6} {:>6} {:>10.1} {:>10} {:>9.1} {:>9} {:>9.1}",
+               name,
+               own.len(),
+               wins,
+               own.iter().map(|r| r.score as f64).sum::<f64>() / games,
+               own.iter().map(|r| r.score).max().unwrap_or(0),
+               own.iter().map(|r| r.max_tile as f64).sum::<f64>() / games,
+               own.iter().map(|r| r.max_tile).max().unwrap_or(0),
+               own.iter().map(|r| r.moves as f64).sum::<f64>() / games)?;
+    }
+    Ok(())
+  }
+
+  // `path` "-" means stdout
+  pub fn write_csv(&self, results: &[GameResult], path: &str) -> io::Result<()> {
+    let mut out: Box<dyn Write> =
+      if path == "-" { Box::new(io::stdout()) } else { Box::new(File::create(path)?) };
+    writeln!(out, "seed,strategy,score,max_tile,moves")?;
+    for r in results {
+      writeln!(out, "{},{},{},{},{}", r.seed, r.strategy, r.score, r.max_tile, r.moves)?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,36 @@
+use super::{GameResult, Tournament};
+use crate::SpawnType;
+
+fn result(strategy: &'static str, seed: u64, score: u32) -> GameResult {
+  GameResult { strategy, seed, score, max_tile: 64, moves: 30 }
+}
+
+#[test]
+fn runs_repeat_with_the_seeds() {
+  let tournament = Tournament::new(&["greedy", "random"], SpawnType::Random, 1..4, 3, 3).unwrap();
+  let summary = |results: Vec<GameResult>| -> Vec<(&str, u64, u32, usize)> {
+    results.iter().map(|r| (r.strategy, r.seed, r.score, r.moves)).collect()
+  };
+  let results = summary(tournament.run());
+  assert_eq!(results.iter().map(|r| (r.0, r.1)).collect::<Vec<_>>(),
+             vec![("greedy", 1), ("random", 1), ("greedy", 2), ("random", 2), ("greedy", 3), ("random", 3)]);
+  assert_eq!(summary(tournament.run()), results);
+}
+
+#[test]
+fn table_counts_wins_per_seed() {
+  let tournament = Tournament::new(&["greedy", "random"], SpawnType::Random, 1..3, 3, 3).unwrap();
+  let results = vec![result("greedy", 1, 100), result("random", 1, 60), result("greedy", 2, 80),
+                     result("random", 2, 80)];
+  let mut table = vec![];
+  tournament.write_table(&results, &mut table).unwrap();
+  assert_eq!(String::from_utf8(table).unwrap(),
+             "strategy    games   wins  avg score  max score  avg tile  max tile avg moves\n\
+              greedy          2      2       90.0        100      64.0        64      30.0\n\
+              random          2      1       70.0         80      64.0        64      30.0\n");
+}
+
+#[test]
+fn unknown_strategies_are_rejected() {
+  assert!(Tournament::new(&["greedy", "lucky"], SpawnType::Random, 1..3, 3, 3).is_err());
+}
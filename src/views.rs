@@ -1,9 +1,56 @@
 mod console;
+mod engine;
 mod ncurses;
 
 pub use self::console::ConsoleView;
-pub use self::ncurses::NCursesView;
+pub use self::engine::EngineView;
+pub use self::ncurses::{Area, NCursesView, Selection};
+
+use crate::game::{Game, GameState, PowerUp};
 
 pub trait View {
   fn update(&self);
 }
+
+// for controllers that may run without showing the game
+pub struct NoView;
+
+impl View for NoView {
+  fn update(&self) {}
+}
+
+// score, next tile, power-ups, goal, moves and time left, and the end of the game, as far as applicable
+fn status_parts(game: &Game) -> Vec<String> {
+  let mut parts = vec![format!("Score: {}", game.score())];
+  if let Some(next_tile) = game.next_tile() {
+    parts.push(format!("Next: {}", next_tile.value));
+  }
+  let power_ups: Vec<String> = PowerUp::ALL
+    .iter()
+    .filter(|&&power_up| game.power_ups(power_up) > 0)
+    .map(|&power_up| format!("{} {}", game.power_ups(power_up), power_up.name()))
+    .collect();
+  if !power_ups.is_empty() {
+    parts.push(format!("Power-ups: {}", power_ups.join(", ")));
+  }
+  if let Some(goal) = game.goal() {
+    parts.push(format!("Goal: {}", goal));
+  }
+  if let Some(moves_left) = game.moves_left() {
+    parts.push(format!("Moves left: {}", moves_left));
+  }
+  if let Some(time_left) = game.time_left() {
+    let seconds = time_left.as_secs() + if time_left.subsec_nanos() > 0 { 1 } else { 0 }; // round up
+    parts.push(format!("Time left: {}:{:02}", seconds / 60, seconds % 60));
+  }
+  if game.is_paused() {
+    parts.push(String::from("PAUSED"));
+  }
+  match game.state() {
+    GameState::Won => parts.push(String::from("GOAL REACHED")),
+    GameState::Over => parts.push(String::from("GAME OVER")),
+    GameState::Finished => parts.push(String::from("FINISHED")),
+    GameState::Running | GameState::Quit => ()
+  }
+  parts
+}
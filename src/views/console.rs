@@ -1,8 +1,8 @@
 use console::Term;
 use std::cell::RefCell;
 
-use super::View;
-use crate::game::{Board, Game, Square};
+use super::{status_parts, View};
+use crate::game::{Game, Hint, Square};
 
 
 pub struct ConsoleView<'a> {
@@ -12,30 +12,65 @@ pub struct ConsoleView<'a> {
 
 
 impl<'a> View for ConsoleView<'a> {
-  fn update(&self) { self.show_board(&self.game.borrow().board); }
+  fn update(&self) {
+    let game = self.game.borrow();
+    self.show_board(&game);
+    // "\r": while keys are read in the background, the terminal is in raw mode
+    print!("{}\r\n", status_parts(&game).join(" | "));
+    if let Some(hint) = game.hint() {
+      self.show_hint(hint);
+    }
+  }
 }
 
 
 impl<'a> ConsoleView<'a> {
-  pub fn new(game: &RefCell<Game>) -> ConsoleView {
+  pub fn new(game: &RefCell<Game>) -> ConsoleView<'_> {
     let term = Term::stdout();
     term.set_title("2048");
     ConsoleView { game, term }
   }
 
-  pub fn term(&self) -> &Term { &self.term }
+  // prints a block of lines with a title, e.g. the help
+  pub fn show_dialog(&self, title: &str, lines: &[String]) {
+    print!("\r\n-- {} --\r\n", title);
+    for line in lines {
+      print!("  {}\r\n", line);
+    }
+  }
 
-  fn show_board(&self, board: &Board) {
-    println!();
+  fn show_board(&self, game: &Game) {
+    let board = &game.board;
+    // the square of the next new tile, in preview mode
+    let next_tile = game.next_tile().and_then(|next_tile| next_tile.at.map(|at| (at, next_tile.value)));
+    print!("\r\n");
     for y in 0..board.size_y() {
       for x in 0..board.size_x() {
         match board.at_xy(x, y) {
-          Square::Empty => print!("[     ]"),
+          Square::Empty => match next_tile {
+            Some((at, value)) if (at.x, at.y) == (x, y) => print!("[{0:^5}]", format!("({})", value)),
+            _ => print!("[     ]")
+          },
           Square::Value(v) => print!("[{0:^5}]", v)
         }
       }
-      println!();
+      print!("\r\n");
+    }
+  }
+
+  fn show_hint(&self, hint: &Hint) {
+    match hint.direction {
+      Some(direction) => print!("Hint: {:?}  (", direction),
+      None => print!("Hint: none  (")
+    }
+    for (i, (direction, rating)) in hint.ratings.iter().enumerate() {
+      let separator = if i == 0 { "" } else { ", " };
+      match rating {
+        Some(rating) => print!("{}{:?}: {:.2}", separator, direction, rating),
+        None => print!("{}{:?}: -", separator, direction)
+      }
     }
+    print!(")\r\n");
   }
 }
 
@@ -0,0 +1,39 @@
+use std::cell::RefCell;
+
+use super::View;
+use crate::game::Game;
+use crate::savegame::SaveGame;
+
+// Prints the state of the game for a program on the other end of the engine protocol (see
+// controllers/engine.rs):
+//
+//   score 12
+//   board
+//   2 . . .
+//   . 4 . .
+//   state running   (won, over or finished)
+//   moves left up   (the directions that change the board)
+//   ok
+pub struct EngineView<'a> {
+  game: &'a RefCell<Game>
+}
+
+impl<'a> View for EngineView<'a> {
+  fn update(&self) { print!("{}", Self::state(&self.game.borrow())); }
+}
+
+impl<'a> EngineView<'a> {
+  pub fn new(game: &RefCell<Game>) -> EngineView<'_> { EngineView { game } }
+
+  // the lines printed for `game`
+  pub fn state(game: &Game) -> String {
+    let mut lines = SaveGame { board: game.board.clone(), score: game.score() }.to_string();
+    lines.push_str(&format!("state {}\n", game.state().name()));
+    lines.push_str("moves");
+    for direction in game.board.possible_directions() {
+      lines.push_str(&format!(" {:?}", direction).to_lowercase());
+    }
+    lines.push_str("\nok\n");
+    lines
+  }
+}
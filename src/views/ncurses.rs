@@ -5,77 +5,288 @@ mod pallete;
 use log::debug;
 use ncurses as nc;
 use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 
 use animator::Animator;
 use ncwindow::NCWindow;
 use pallete::Pallete;
 
-use crate::game::{Coord, Game, Move};
-use crate::views::View;
+use crate::game::{Coord, Direction, Game, Hint, Move, NextTile, Square};
+use crate::signals;
+use crate::views::{status_parts, View};
 
 // NCurses HOWTO: http://www.tldp.org/HOWTO/NCURSES-Programming-HOWTO/
 // man pages: man 3x <function>
 //
 
+// ncurses is initialized by the first view and ended by the last one
+static OPEN_VIEWS: AtomicUsize = AtomicUsize::new(0);
+
+// the part of the screen used by a view
+pub enum Area {
+  Full,
+  LeftHalf,
+  RightHalf,
+}
+
+// a box of lines with a title: additional information right of the board, or a dialog over the board
+struct Panel {
+  title: String,
+  lines: Vec<String>,
+}
+
+// squares being picked by the player, e.g. for a power-up
+pub struct Selection {
+  pub cursor: Coord,
+  pub marked: Option<Coord>, // picked before
+  pub prompt: String,
+}
+
 pub struct NCursesView<'a> {
   game: &'a RefCell<Game>,
   pallete: Pallete,
   animator: Animator,
   last_shown_move: Cell<usize>,
-  stdscr: NCWindow,
+  area: Area,
+  caption: RefCell<String>,
+  side_panel: RefCell<Option<Panel>>,
+  selection: RefCell<Option<Selection>>,
+  dialog: RefCell<Option<Panel>>,
+  board_box: Cell<Option<(i32, i32, i32, i32)>>, // top, left, height and width on the screen, as last drawn
 }
 
 impl<'a> View for NCursesView<'a> {
   fn update(&self) {
     debug!("Start update view");
-    nc::erase(); // like clear(), but without implicit refresh()
-    let board_box_window = self.position_board_in(&self.stdscr);
+    if self.last_shown_move.get() == self.game.borrow().move_count() {
+      // move has already been animated ⇒ only show last frame
+      self.animator.stop();
+      self.draw(1.0);
+    } else {
+      debug!("Start animation");
+      self.last_shown_move.set(self.game.borrow().move_count());
+      self.animator.animate(|t| self.draw(t));
+      debug!("End animation");
+    }
+    debug!("End update view");
+  }
+}
+
+impl<'a> NCursesView<'a> {
+  const BORDER_WIDTH: i32 = 1;
+  const SIDE_PANEL_WIDTH: i32 = 26;
+
+  // Like update(), but only shows the first frame of an animation. The rest is drawn by draw_next_frame(),
+  // so that an event loop can wait for input in between.
+  pub fn start_update(&self) {
+    if self.last_shown_move.get() == self.game.borrow().move_count() {
+      self.update();
+    } else {
+      self.last_shown_move.set(self.game.borrow().move_count());
+      self.animator.start();
+      self.draw_next_frame();
+    }
+  }
+
+  pub fn is_animating(&self) -> bool { self.animator.is_running() }
+
+  pub fn frame_delay(&self) -> Duration { self.animator.frame_delay() }
+
+  pub fn draw_next_frame(&self) {
+    if let Some(t) = self.animator.next_frame() {
+      self.draw(t);
+    }
+  }
+
+  // skips the rest of a running animation
+  pub fn finish_update(&self) {
+    if self.animator.is_running() {
+      self.animator.stop();
+      self.draw(1.0);
+    }
+  }
+
+  fn draw(&self, t: f32) {
+    let area_window = self.area_window();
+    nc::werase(area_window.0); // like wclear(), but without implicit refresh()
+    let (board_area_window, side_panel_window) = self.split_off_side_panel(&area_window);
+    if let Some(side_panel_window) = &side_panel_window {
+      self.show_side_panel(side_panel_window);
+    }
+    let board_box_window = self.position_board_in(&board_area_window);
+    let (mut top, mut left) = (0, 0);
+    nc::getbegyx(board_box_window.0, &mut top, &mut left);
+    let (height, width) = board_box_window.size();
+    self.board_box.set(Some((top, left, height, width)));
     // TODO:
     // if let GameState::Over = game.state() {
     //  nc::wattr_on(board_win, nc::A_BLINK());
     // }
     let board_window = self.boxed_subwindow(&board_box_window);
+    self.show_caption_on_box(&board_box_window);
+    if let Some(selection) = self.selection.borrow().as_ref() {
+      self.show_prompt_on_box(&selection.prompt, &board_box_window);
+    } else if let Some(hint) = self.game.borrow().hint() {
+      self.show_hint_on_box(hint, &board_box_window);
+    }
     //    nc::wattr_off(board_win, nc::A_BLINK());
     //    if let GameState::Over = game.state() {
     //      nc::wattr_on(board_win, nc::A_STANDOUT());
     //    }
     //    nc::wattr_off(board_win, nc::A_STANDOUT());
+    nc::touchwin(nc::stdscr()); // changes in subwindows, e.g. the status, are not tracked by stdscr
     nc::wnoutrefresh(nc::stdscr());
-    let draw_frame = |t| {
-      debug!("Draw frame, t={:?}", t);
-      nc::werase(board_window.0);
-      self.display_animation_frame(&board_window, t);
-      nc::wnoutrefresh(board_window.0);
-      nc::doupdate();
-    };
-    if self.last_shown_move.get() == self.game.borrow().move_count() {
-      // move has already been animated ⇒ only show last frame
-      draw_frame(1.0);
-    } else {
-      debug!("Start animation");
-      self.animator.animate(draw_frame);
-      debug!("End animation");
-      self.last_shown_move.set(self.game.borrow().move_count());
+    debug!("Draw frame, t={:?}", t);
+    self.display_animation_frame(&board_window, t);
+    nc::wnoutrefresh(board_window.0);
+    if let Some(dialog_window) = self.dialog_window_over(&board_box_window) {
+      self.show_dialog(&dialog_window);
+      nc::touchwin(nc::stdscr()); // getch() refreshes stdscr, which would hide a window of its own
+      nc::wnoutrefresh(nc::stdscr());
     }
-    debug!("End update view");
+    nc::doupdate();
   }
-}
 
-impl<'a> NCursesView<'a> {
-  const BORDER_WIDTH: i32 = 1;
+  pub fn new(game: &'a RefCell<Game>) -> Self { Self::in_area(game, Area::Full) }
 
-  pub fn new(game: &'a RefCell<Game>) -> Self {
-    nc::initscr();
-    nc::start_color();
-    nc::curs_set(nc::CURSOR_VISIBILITY::CURSOR_INVISIBLE);
-    nc::refresh(); // required for first wrefresh to work
+  pub fn in_area(game: &'a RefCell<Game>, area: Area) -> Self {
+    if OPEN_VIEWS.fetch_add(1, Ordering::SeqCst) == 0 {
+      nc::initscr();
+      nc::start_color();
+      nc::curs_set(nc::CURSOR_VISIBILITY::CURSOR_INVISIBLE);
+      nc::refresh(); // required for first wrefresh to work
+      signals::set_curses_active(true);
+    }
     let last_shown_move = game.borrow().move_count();
     NCursesView {
       game,
       pallete: Pallete::new(),
       animator: Animator::new(0.6, 50),
       last_shown_move: Cell::new(last_shown_move),
-      stdscr: NCWindow::new(None, nc::stdscr(), "stdscr"),
+      area,
+      caption: RefCell::new(String::new()),
+      side_panel: RefCell::new(None),
+      selection: RefCell::new(None),
+      dialog: RefCell::new(None),
+      board_box: Cell::new(None),
+    }
+  }
+
+  // shown in the top line of the box, before the status
+  pub fn set_caption(&self, caption: &str) { self.caption.replace(caption.to_string()); }
+
+  pub fn set_side_panel(&self, title: &str, lines: &[String]) {
+    self.side_panel.replace(Some(Panel { title: title.to_string(), lines: lines.to_vec() }));
+  }
+
+  // shown over the board until cleared
+  pub fn set_dialog(&self, title: &str, lines: &[String]) {
+    self.dialog.replace(Some(Panel { title: title.to_string(), lines: lines.to_vec() }));
+  }
+
+  pub fn clear_dialog(&self) { self.dialog.replace(None); }
+
+  // None: no squares are being picked
+  pub fn set_selection(&self, selection: Option<Selection>) { self.selection.replace(selection); }
+
+  // None, until the board has been drawn
+  pub fn board_box(&self) -> Option<(i32, i32, i32, i32)> { self.board_box.get() }
+
+  fn area_window(&self) -> NCWindow {
+    let (mut height, mut width) = (0, 0);
+    nc::getmaxyx(nc::stdscr(), &mut height, &mut width);
+    let (left, width) = match self.area {
+      Area::Full => (0, width),
+      Area::LeftHalf => (0, width / 2),
+      Area::RightHalf => (width / 2, width - width / 2),
+    };
+    NCWindow::new(None, nc::derwin(nc::stdscr(), height, width, 0, left), "area")
+  }
+
+  // returns the window for the board, and the window for the side panel, if there is one
+  fn split_off_side_panel(&self, area_window: &NCWindow) -> (NCWindow, Option<NCWindow>) {
+    let (height, width) = area_window.size();
+    let board_width = if self.side_panel.borrow().is_some() && width >= 2 * Self::SIDE_PANEL_WIDTH {
+      width - Self::SIDE_PANEL_WIDTH
+    } else {
+      width
+    };
+    let board_area_window =
+      NCWindow::new(Some(area_window), nc::derwin(area_window.0, height, board_width, 0, 0), "board area");
+    let side_panel_window = if board_width < width {
+      Some(NCWindow::new(
+        Some(area_window),
+        nc::derwin(area_window.0, height, width - board_width, 0, board_width),
+        "side panel",
+      ))
+    } else {
+      None
+    };
+    (board_area_window, side_panel_window)
+  }
+
+  fn show_side_panel(&self, window: &NCWindow) {
+    if let Some(side_panel) = &*self.side_panel.borrow() {
+      self.show_panel(side_panel, window);
+    }
+  }
+
+  fn show_panel(&self, panel: &Panel, window: &NCWindow) {
+    nc::box_(window.0, 0, 0);
+    nc::mvwaddstr(window.0, 0, 1, &format!(" {} ", panel.title));
+    let (height, width) = window.size();
+    for (i, line) in panel.lines.iter().enumerate().take((height - 2).max(0) as usize) {
+      nc::wmove(window.0, i as i32 + 1, 2);
+      for c in line.chars().take((width - 4).max(0) as usize) {
+        // without wide character support, only ASCII and the line drawing characters can be shown
+        match c {
+          '←' => nc::waddch(window.0, nc::ACS_LARROW()),
+          '→' => nc::waddch(window.0, nc::ACS_RARROW()),
+          '↑' => nc::waddch(window.0, nc::ACS_UARROW()),
+          '↓' => nc::waddch(window.0, nc::ACS_DARROW()),
+          c if c.is_ascii() => nc::waddch(window.0, c as nc::chtype),
+          _ => nc::waddch(window.0, '?' as nc::chtype),
+        };
+      }
+    }
+  }
+
+  // centered over the board, but at most as large as the screen. None, if there is no dialog.
+  fn dialog_window_over(&self, board_box_window: &NCWindow) -> Option<NCWindow> {
+    let dialog = self.dialog.borrow();
+    let dialog = dialog.as_ref()?;
+    let text_width = dialog.lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    let text_width = text_width.max(dialog.title.chars().count() + 2) as i32;
+    let (mut screen_height, mut screen_width) = (0, 0);
+    nc::getmaxyx(nc::stdscr(), &mut screen_height, &mut screen_width);
+    let height = (dialog.lines.len() as i32 + 2).min(screen_height);
+    let width = (text_width + 4).min(screen_width);
+    let (mut top, mut left) = (0, 0);
+    nc::getbegyx(board_box_window.0, &mut top, &mut left);
+    let (box_height, box_width) = board_box_window.size();
+    let top = (top + (box_height - height) / 2).clamp(0, screen_height - height);
+    let left = (left + (box_width - width) / 2).clamp(0, screen_width - width);
+    Some(NCWindow::new(None, nc::derwin(nc::stdscr(), height, width, top, left), "dialog"))
+  }
+
+  fn show_dialog(&self, window: &NCWindow) {
+    if let Some(dialog) = &*self.dialog.borrow() {
+      nc::werase(window.0);
+      self.show_panel(dialog, window);
+    }
+  }
+
+  fn show_caption_on_box(&self, box_window: &NCWindow) {
+    let mut parts = vec![];
+    if !self.caption.borrow().is_empty() {
+      parts.push(self.caption.borrow().clone());
+    }
+    parts.extend(status_parts(&self.game.borrow()));
+    let label = format!(" {} ", parts.join(" | "));
+    let (_, width) = box_window.size();
+    if (label.chars().count() as i32) < width - 2 {
+      nc::mvwaddstr(box_window.0, 0, 1, &label);
     }
   }
 
@@ -116,6 +327,41 @@ impl<'a> NCursesView<'a> {
     )
   }
 
+  // draws all ratings into the bottom line of the box, and an arrow into the middle of the box side in
+  // the hinted direction
+  fn show_hint_on_box(&self, hint: &Hint, box_window: &NCWindow) {
+    let (height, width) = box_window.size();
+    let ratings: Vec<String> = hint
+      .ratings
+      .iter()
+      .map(|(direction, rating)| match rating {
+        Some(rating) => format!("{:?} {:.1}", direction, rating),
+        None => format!("{:?} -", direction),
+      })
+      .collect();
+    let label = format!(" {} ", ratings.join(" | "));
+    if (label.len() as i32) < width - 2 {
+      nc::mvwaddstr(box_window.0, height - 1, 1, &label);
+    }
+    if let Some(direction) = hint.direction {
+      let (y, x, arrow) = match direction {
+        Direction::Left => (height / 2, 0, nc::ACS_LARROW()),
+        Direction::Right => (height / 2, width - 1, nc::ACS_RARROW()),
+        Direction::Up => (0, width / 2, nc::ACS_UARROW()),
+        Direction::Down => (height - 1, width / 2, nc::ACS_DARROW()),
+      };
+      nc::mvwaddch(box_window.0, y, x, arrow | nc::A_STANDOUT());
+    }
+  }
+
+  fn show_prompt_on_box(&self, prompt: &str, box_window: &NCWindow) {
+    let (height, width) = box_window.size();
+    let label = format!(" {} ", prompt);
+    if (label.chars().count() as i32) < width - 2 {
+      nc::mvwaddstr(box_window.0, height - 1, 1, &label);
+    }
+  }
+
   fn calc_optimal_board_win(&self, max_height: i32, max_width: i32) -> (i32, i32) {
     let board = &self.game.borrow().board;
     // calculate dimensions such that dimension % game.size == 0
@@ -168,6 +414,42 @@ impl<'a> NCursesView<'a> {
           let square_window = self.position_square_in(*at, *at, board_window, t_move);
           self.show_square_in_window(*value, &square_window);
         },
+        Move::Vanish { at, value } =>
+          if t_global < t_appear {
+            let square_window = self.position_square_in(*at, *at, board_window, t_global);
+            self.show_square_in_window(*value, &square_window);
+          },
+      }
+    }
+    if t_global == 1.0 {
+      self.show_next_tile_in(board_window);
+      self.show_selection_in(board_window);
+    }
+  }
+
+  // in preview mode: the value of the next new tile on its (still empty) square
+  fn show_next_tile_in(&self, board_window: &NCWindow) {
+    let game = self.game.borrow();
+    if let Some(NextTile { value, at: Some(at) }) = game.next_tile() {
+      if game.board.at(*at) == Square::Empty {
+        let square_window = self.position_square_in(*at, *at, board_window, 1.0);
+        let (win_height, win_width) = square_window.size();
+        let label = format!("({})", value);
+        nc::mvwaddstr(square_window.0, win_height / 2, (win_width - label.len() as i32) / 2, &label);
+      }
+    }
+  }
+
+  // the marked square with a bold border, the cursor with a reversed one
+  fn show_selection_in(&self, board_window: &NCWindow) {
+    if let Some(selection) = self.selection.borrow().as_ref() {
+      let marked = selection.marked.iter().map(|&at| (at, nc::A_BOLD()));
+      let squares = marked.chain(Some((selection.cursor, nc::A_REVERSE())));
+      for (at, attribute) in squares {
+        let square_window = self.position_square_in(at, at, board_window, 1.0);
+        nc::wattr_on(square_window.0, attribute);
+        nc::box_(square_window.0, 0, 0);
+        nc::wattr_off(square_window.0, attribute);
       }
     }
   }
@@ -218,9 +500,15 @@ impl<'a> NCursesView<'a> {
 
 impl<'a> Drop for NCursesView<'a> {
   fn drop(&mut self) {
+    if OPEN_VIEWS.fetch_sub(1, Ordering::SeqCst) > 1 {
+      return; // another view still uses ncurses
+    }
     // reset terminal properties (e.g. make cursor visible again)
-    nc::endwin(); // this also clears the screen and destroys any error output
+    if !nc::isendwin() {
+      // not yet ended by the panic hook
+      nc::endwin(); // this also clears the screen and destroys any error output
+    }
+    signals::set_curses_active(false);
     debug!("fin de ncurses.");
-    // TODO: register endwin on CTRL-C
   }
 }
@@ -1,10 +1,17 @@
+#[cfg(test)]
+mod test;
+
 use log::info;
+use std::cell::Cell;
 use std::thread;
 use std::time::{Duration, Instant};
 
+// Animations can be played blocking with `animate()` or frame by frame with `start()` and `next_frame()`,
+// e.g. from an event loop that may cut them short with `stop()`.
 pub struct Animator {
   frame_delay: Duration,
-  steps:       u16
+  steps:       u16,
+  next_step:   Cell<Option<u16>> // None, if no animation is running
 }
 
 impl Animator {
@@ -16,17 +23,32 @@ impl Animator {
           fps,
           steps,
           frame_delay.as_micros());
-    Self { frame_delay, steps: steps as u16 }
+    Self { frame_delay, steps, next_step: Cell::new(None) }
+  }
+
+  pub fn frame_delay(&self) -> Duration { self.frame_delay }
+
+  pub fn start(&self) { self.next_step.set(Some(0)); }
+
+  pub fn stop(&self) { self.next_step.set(None); }
+
+  pub fn is_running(&self) -> bool { self.next_step.get().is_some() }
+
+  // t of the next frame: exactly 0.0 for the first and exactly 1.0 for the last one. None, if no
+  // animation is running.
+  pub fn next_frame(&self) -> Option<f32> {
+    let step = self.next_step.get()?;
+    self.next_step.set(if step < self.steps { Some(step + 1) } else { None });
+    Some(step as f32 / self.steps as f32)
   }
 
   pub fn animate<V>(&self, visualizer: V)
     where V: Fn(f32) {
-    let mut max_duration_μs = self.show_frame_and_sleep(&visualizer, 0.0); // guarantee exact 0.0 as first frame
-    for i in 1..self.steps {
-      max_duration_μs =
-        max_duration_μs.max(self.show_frame_and_sleep(&visualizer, i as f32 / self.steps as f32));
+    let mut max_duration_μs = 0;
+    self.start();
+    while let Some(t) = self.next_frame() {
+      max_duration_μs = max_duration_μs.max(self.show_frame_and_sleep(&visualizer, t));
     }
-    visualizer(1.0); // guarantee exact 1.0 as last frame
     info!("max frame rendering duration: {}μs", max_duration_μs);
   }
 
@@ -35,7 +57,9 @@ impl Animator {
     let start_time = Instant::now();
     visualizer(t);
     let render_duration = start_time.elapsed();
-    thread::sleep(self.frame_delay - render_duration);
+    if let Some(delay) = self.frame_delay.checked_sub(render_duration).filter(|_| self.is_running()) {
+      thread::sleep(delay);
+    }
     render_duration.as_micros()
   }
 }
@@ -0,0 +1,22 @@
+use super::Animator;
+
+#[test]
+fn frames_run_from_0_to_1() {
+  let animator = Animator::new(0.1, 50);
+  assert_eq!(animator.next_frame(), None);
+  animator.start();
+  let frames: Vec<f32> = std::iter::from_fn(|| animator.next_frame()).collect();
+  assert_eq!((frames.len(), frames[0], frames[5]), (6, 0.0, 1.0));
+  assert!(frames.windows(2).all(|pair| pair[0] < pair[1]));
+  assert!(!animator.is_running());
+}
+
+#[test]
+fn stop_ends_the_frames() {
+  let animator = Animator::new(0.1, 50);
+  animator.start();
+  animator.next_frame();
+  animator.stop();
+  assert!(!animator.is_running());
+  assert_eq!(animator.next_frame(), None);
+}